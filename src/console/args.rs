@@ -0,0 +1,59 @@
+use crate::error::BtrError;
+use chrono::NaiveDate;
+
+/* Returns the token following the '--name' option, if present. */
+pub fn option_value<'a>(args: &[&'a str], name: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|&arg| arg == name)
+        .and_then(|pos| args.get(pos + 1).copied())
+}
+
+/* Returns the token following every occurrence of the '--name' option. */
+pub fn option_values<'a>(args: &[&'a str], name: &str) -> Vec<&'a str> {
+    args.windows(2)
+        .filter(|pair| pair[0] == name)
+        .map(|pair| pair[1])
+        .collect()
+}
+
+/* Joins all tokens following the '--name' option up to the next option. */
+pub fn option_text(args: &[&str], name: &str) -> Option<String> {
+    let pos = args.iter().position(|&arg| arg == name)?;
+    let text = args[pos + 1..]
+        .iter()
+        .take_while(|arg| !arg.starts_with("--"))
+        .copied()
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    (!text.is_empty()).then_some(text)
+}
+
+pub fn has_flag(args: &[&str], name: &str) -> bool {
+    args.contains(&name)
+}
+
+/* Tokens before the first option. */
+pub fn positional<'a, 'b>(args: &'b [&'a str]) -> &'b [&'a str] {
+    let end = args
+        .iter()
+        .position(|arg| arg.starts_with("--"))
+        .unwrap_or(args.len());
+
+    &args[..end]
+}
+
+pub fn parse_date(value: &str) -> Result<NaiveDate, BtrError> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| {
+        BtrError::InvalidData(Some(format!(
+            "'{}' is not a valid date. Expected YYYY-MM-DD.",
+            value
+        )))
+    })
+}
+
+pub fn parse_amount(value: &str) -> Result<f32, BtrError> {
+    value
+        .parse::<f32>()
+        .map_err(|_| BtrError::InvalidData(Some(format!("'{}' is not a valid amount.", value))))
+}
@@ -1,8 +1,5 @@
 use crate::{
-    console::{
-        cmd::CommandNode,
-        handlers::{self, show_expenses_handler},
-    },
+    console::{cmd::CommandNode, handlers},
     database::manager::TrackerManager,
     error::BtrError,
 };
@@ -12,6 +9,8 @@ use std::io::{Write, stdin, stdout};
 pub struct TrackerCli {
     cmd_tree: CommandNode,
     pub tracker_manager: TrackerManager,
+    /* Sheet the user chose to keep after its period was over, not asked about again. */
+    pub kept_sheet: Option<String>,
 }
 
 impl TrackerCli {
@@ -21,13 +20,48 @@ impl TrackerCli {
                 CommandNode::new("add", "Add a new record to a budget tracker.", None)
                     .add_child(CommandNode::new(
                         "expense",
-                        "Add a new expense record to an active sheet.",
-                        Some(handlers::add_expense_handler),
+                        "Add a new expense record to an active sheet. Asks for a category and an amount, with --details alone also for a split, payee, note, tags, account and payer. One-line form: <category> <amount> or <total> --line <category> <amount> [note] ... [--date] [--payee] [--note] [--tags] [--account] [--paid-by <person>] [--split equal[:names]|percent:name=pct,...|exact:name=amount,...].",
+                        Some(handlers::expense::add_expense_handler),
+                    ))
+                    .add_child(CommandNode::new(
+                        "income",
+                        "Add an income record to an active sheet. One-line form: <source> <amount> [--date] [--note] [--account].",
+                        Some(handlers::income::add_income_handler),
+                    ))
+                    .add_child(CommandNode::new(
+                        "debt",
+                        "Add money lent to or borrowed from someone. One-line form: <lent|borrowed> <counterparty> <amount> [--interest <annual percent>] [--date] [--note] [--account].",
+                        Some(handlers::debt::add_debt_handler),
+                    ))
+                    .add_child(CommandNode::new(
+                        "repayment",
+                        "Pay back a debt, by default the whole outstanding balance. One-line form: <debt ID> [<amount>] [--date] [--note] [--account].",
+                        Some(handlers::debt::add_repayment_handler),
+                    ))
+                    .add_child(CommandNode::new(
+                        "goal",
+                        "Add a savings goal. One-line form: <name> <target> --by <YYYY-MM|YYYY-MM-DD>.",
+                        Some(handlers::goal::add_goal_handler),
+                    ))
+                    .add_child(CommandNode::new(
+                        "contribution",
+                        "Put money towards a savings goal. One-line form: <goal> <amount> [--date].",
+                        Some(handlers::goal::add_contribution_handler),
+                    ))
+                    .add_child(CommandNode::new(
+                        "refund",
+                        "Add a refund of an earlier expense to the active sheet: [<amount>] [--date] [--note] [query to find the expense].",
+                        Some(handlers::expense::add_refund_handler),
+                    ))
+                    .add_child(CommandNode::new(
+                        "transfer",
+                        "Move money between accounts: <amount> --from <account> --to <account> [--date] [--note].",
+                        Some(handlers::account::add_transfer_handler),
                     ))
                     .add_child(CommandNode::new(
                         "sheet",
-                        "Add a new expense sheet to an tracker database.",
-                        Some(handlers::add_sheet_handler),
+                        "Add a new expense sheet to an tracker database. One-line form: [<period>] or [<name>] [--period <period>], the period given as 2025-03, 2025-Q3, 2025-W14, FY2025, 2025-03-10..2025-04-09 or payday:25, or --next for the period after the active sheet. The current month by default.",
+                        Some(handlers::sheet::add_sheet_handler),
                     )),
             )
             .add_child(
@@ -39,12 +73,27 @@ impl TrackerCli {
                 .add_child(CommandNode::new(
                     "sheet",
                     "Removes selected expense sheet from the tracker database",
-                    Some(handlers::delete_sheet_handler),
+                    Some(handlers::sheet::delete_sheet_handler),
                 ))
                 .add_child(CommandNode::new(
                     "expense",
-                    "Removes selected expense record from the active sheet",
-                    Some(handlers::delete_expense_handler),
+                    "Removes selected expense record from the active sheet. Accepts a query to narrow the list.",
+                    Some(handlers::expense::delete_expense_handler),
+                ))
+                .add_child(CommandNode::new(
+                    "goal",
+                    "Removes the savings goal with the given name.",
+                    Some(handlers::goal::delete_goal_handler),
+                ))
+                .add_child(CommandNode::new(
+                    "income",
+                    "Removes selected income record from the active sheet.",
+                    Some(handlers::income::delete_income_handler),
+                ))
+                .add_child(CommandNode::new(
+                    "expenses",
+                    "Removes all expenses matching a query, e.g. 'where category = Cafe since 2025-01-01'.",
+                    Some(handlers::expense::delete_expenses_handler),
                 )),
             )
             .add_child(CommandNode::new(
@@ -56,30 +105,157 @@ impl TrackerCli {
                 CommandNode::new("show", "Print a configuration of selected category.", None)
                     .add_child(CommandNode::new(
                         "expenses",
-                        "Print expenses from the active month.",
-                        Some(handlers::show_expenses_handler),
+                        "Print expenses from the active month. Filters: --period, --from, --to, --category, --tag, --payee, --min, --max, or a query: where <cond> since <date> until <date> order by <field> [desc] limit <n>. Add --stats for statistics, --stats --json for structured output.",
+                        Some(handlers::report::show_expenses_handler),
+                    ))
+                    .add_child(
+                        CommandNode::new(
+                            "chart",
+                            "Draw a chart of the active sheet. Options: filters as in 'show expenses'.",
+                            None,
+                        )
+                        .add_child(CommandNode::new(
+                            "bars",
+                            "Horizontal bar chart of spending per category.",
+                            Some(handlers::report::show_chart_bars_handler),
+                        ))
+                        .add_child(CommandNode::new(
+                            "sparkline",
+                            "Sparkline of daily spending across the period.",
+                            Some(handlers::report::show_chart_sparkline_handler),
+                        ))
+                        .add_child(CommandNode::new(
+                            "heatmap",
+                            "Calendar heatmap of spending per day.",
+                            Some(handlers::report::show_chart_heatmap_handler),
+                        )),
+                    )
+                    .add_child(CommandNode::new(
+                        "compare",
+                        "Compare category totals of two periods: [<base>|prev|yoy] [<compared>]. The compared period defaults to the active sheet.",
+                        Some(handlers::report::show_compare_handler),
+                    ))
+                    .add_child(CommandNode::new(
+                        "forecast",
+                        "Forecast the spending of the active sheet's period from earlier sheets.",
+                        Some(handlers::report::show_forecast_handler),
+                    ))
+                    .add_child(CommandNode::new(
+                        "accounts",
+                        "Print the balance of every account and the money moved in the active sheet's period. Options: --period.",
+                        Some(handlers::account::show_accounts_handler),
+                    ))
+                    .add_child(CommandNode::new(
+                        "balances",
+                        "Print who owes whom for shared expenses across all sheets.",
+                        Some(handlers::shared::show_balances_handler),
+                    ))
+                    .add_child(CommandNode::new(
+                        "subscriptions",
+                        "List charges repeating at a regular interval with their monthly and annualized cost. Options: --all to include stopped ones.",
+                        Some(handlers::schedule::show_subscriptions_handler),
+                    ))
+                    .add_child(CommandNode::new(
+                        "envelopes",
+                        "Show the money carried, assigned, spent and available in every category envelope of the active sheet.",
+                        Some(handlers::envelope::show_envelopes_handler),
+                    ))
+                    .add_child(CommandNode::new(
+                        "goals",
+                        "List savings goals with their progress, the monthly amount needed to reach them on time and the projected completion.",
+                        Some(handlers::goal::show_goals_handler),
+                    ))
+                    .add_child(CommandNode::new(
+                        "debts",
+                        "List outstanding debts with interest and repayments. Not counted as spending. Options: --all to include paid back ones.",
+                        Some(handlers::debt::show_debts_handler),
+                    ))
+                    .add_child(CommandNode::new(
+                        "installments",
+                        "List outstanding installment plans with their payments and remaining balance. Options: --all to include paid off ones.",
+                        Some(handlers::schedule::show_installments_handler),
+                    ))
+                    .add_child(CommandNode::new(
+                        "anomalies",
+                        "List unusual expenses compared with history. Options: filters as in 'show expenses'.",
+                        Some(handlers::report::show_anomalies_handler),
                     ))
                     .add_child(CommandNode::new(
                         "sheets",
                         "Print a list of all available expense sheets.",
-                        Some(handlers::show_sheets_handler),
+                        Some(handlers::sheet::show_sheets_handler),
                     ))
                     .add_child(CommandNode::new(
                         "categories",
                         "List all available expense categories.",
-                        Some(handlers::show_categories_handler),
+                        Some(handlers::expense::show_categories_handler),
                     )),
             )
+            .add_child(
+                CommandNode::new(
+                    "export",
+                    "Export records of the active sheet or of the filters given.",
+                    None,
+                )
+                .add_child(CommandNode::new(
+                    "csv",
+                    "Export records to a CSV file. Options: filters as in 'show expenses', --out <path>.",
+                    Some(handlers::export::export_csv_handler),
+                ))
+                .add_child(CommandNode::new(
+                    "json",
+                    "Export records to a JSON file. Options: filters as in 'show expenses', --out <path>.",
+                    Some(handlers::export::export_json_handler),
+                )),
+            )
+            .add_child(
+                CommandNode::new("report", "Generate a shareable expenses report.", None)
+                    .add_child(CommandNode::new(
+                        "html",
+                        "Write a self-contained HTML report. Options: --period <YYYY|YYYY-MM|month|year>, filters as in 'show expenses', --out <path>.",
+                        Some(handlers::report::report_html_handler),
+                    )),
+            )
+            .add_child(CommandNode::new(
+                "reconcile",
+                "Match records of an account with a bank statement: <account> [--balance <amount>] [--date YYYY-MM-DD].",
+                Some(handlers::account::reconcile_handler),
+            ))
+            .add_child(CommandNode::new(
+                "assign",
+                "Put income into a category envelope, a negative amount takes it out. One-line form: <category> <amount> [--date YYYY-MM-DD].",
+                Some(handlers::envelope::assign_handler),
+            ))
+            .add_child(CommandNode::new(
+                "settle",
+                "Propose transfers settling shared expenses and record them in the sheet holding today.",
+                Some(handlers::shared::settle_handler),
+            ))
             .add_child(CommandNode::new(
                 "select",
                 "Select an active sheet which will be updated with a new expenses logs.",
-                Some(handlers::select_handler),
+                Some(handlers::sheet::select_handler),
             ));
 
-        Ok(Self {
-            cmd_tree: cmd_tree,
-            tracker_manager: TrackerManager::new()?,
-        })
+        let mut tracker_manager = TrackerManager::new()?;
+
+        /* Scheduled charges may have become due since the active sheet was last opened. */
+        if let Some(sheet_name) = tracker_manager
+            .get_active_sheet()
+            .as_ref()
+            .map(|sheet| sheet.name.clone())
+        {
+            handlers::sheet::add_due_charges(&mut tracker_manager, &sheet_name)?;
+        }
+
+        let mut cli = Self {
+            cmd_tree,
+            tracker_manager,
+            kept_sheet: None,
+        };
+        handlers::sheet::roll_over(&mut cli)?;
+
+        Ok(cli)
     }
 
     pub fn user_input() -> Result<String, BtrError> {
@@ -108,13 +284,10 @@ impl TrackerCli {
                     eprintln!("> FAILED: Unknown command: {}", context.join(" "));
                 }
             } else if let Some(cmd_node) = self.cmd_tree.find_command(&tokens) {
-                if let Some(handler_fn) = cmd_node.handler {
-                    match handler_fn(self, &tokens) {
-                        Err(e) => {
-                            eprintln!("! Operation finished with an error:\n!   {}", e);
-                        }
-                        _ => {}
-                    }
+                if let Some(handler_fn) = cmd_node.handler
+                    && let Err(e) = handler_fn(self, &tokens)
+                {
+                    eprintln!("! Operation finished with an error:\n!   {}", e);
                 }
             } else {
                 eprintln!("> FAILED: Unknown command: {}", tokens.join(" "));
@@ -1,24 +1,22 @@
 use crate::{console::cli::TrackerCli, error::BtrError};
 
+pub type CommandHandler = fn(&mut TrackerCli, &[&str]) -> Result<(), BtrError>;
+
 pub struct CommandNode {
     cmd: String,
     description: String,
     children: Vec<CommandNode>,
-    pub handler: Option<fn(&mut TrackerCli, &[&str]) -> Result<(), BtrError>>,
+    pub handler: Option<CommandHandler>,
     extra_args: bool,
 }
 
 impl CommandNode {
-    pub fn new(
-        cmd: &str,
-        description: &str,
-        handler: Option<fn(&mut TrackerCli, &[&str]) -> Result<(), BtrError>>,
-    ) -> Self {
+    pub fn new(cmd: &str, description: &str, handler: Option<CommandHandler>) -> Self {
         Self {
             cmd: cmd.to_string(),
             description: description.to_string(),
             children: Vec::new(),
-            handler: handler,
+            handler,
             extra_args: handler.is_some(),
         }
     }
@@ -0,0 +1,445 @@
+use super::{
+    confirm, optional_input,
+    sheet::{add_to_sheet, added_to},
+};
+use crate::{
+    console::{args, cli::TrackerCli},
+    database::{
+        config::{Account, account::find_account},
+        expense::{ClearStatus, ExpenseRecord, ExpenseSheet, IncomeRecord, TransferRecord},
+        manager::TrackerManager,
+        periods::Period,
+        query::{self, RecordQuery},
+    },
+    error::BtrError,
+    report::accounts,
+};
+use chrono::{NaiveDate, Utc};
+
+/* Account given with '--account', checked against the configured ones. */
+pub(super) fn account_from_args(
+    accounts: &[Account],
+    args: &[&str],
+) -> Result<Option<String>, BtrError> {
+    args::option_text(args, "--account")
+        .map(|name| known_account(accounts, &name))
+        .transpose()
+}
+
+fn known_account(accounts: &[Account], name: &str) -> Result<String, BtrError> {
+    find_account(accounts, name)
+        .map(|account| account.name.clone())
+        .ok_or_else(|| BtrError::InvalidData(Some(format!("Unknown account '{}'.", name))))
+}
+
+/* Asks for an account when any is configured, an empty answer leaves the default one. */
+pub(super) fn account_from_prompt(accounts: &[Account]) -> Result<Option<String>, BtrError> {
+    if accounts.is_empty() {
+        return Ok(None);
+    }
+
+    println!("!> Select an account (optional):");
+    for (idx, account) in accounts.iter().enumerate() {
+        let default = if account.default { " (default)" } else { "" };
+        println!("> {}: {}{}", idx + 1, account.name, default);
+    }
+
+    loop {
+        let Some(input) = optional_input()? else {
+            return Ok(None);
+        };
+        match input.parse::<usize>() {
+            Ok(idx) if idx >= 1 && idx <= accounts.len() => {
+                return Ok(Some(accounts[idx - 1].name.clone()));
+            }
+            _ => println!(
+                "! Invalid input. Select a number between 1 and {}.",
+                accounts.len()
+            ),
+        }
+    }
+}
+
+/* add transfer <amount> --from <account> --to <account> [--date YYYY-MM-DD] [--note <text>] */
+pub fn add_transfer_handler(cli: &mut TrackerCli, args: &[&str]) -> Result<(), BtrError> {
+    let accounts = cli.tracker_manager.get_accounts();
+
+    let (Some(amount), Some(from), Some(to)) = (
+        args::positional(&args[2..]).first(),
+        args::option_text(args, "--from"),
+        args::option_text(args, "--to"),
+    ) else {
+        return Err(BtrError::InvalidData(Some(String::from(
+            "Usage: add transfer <amount> --from <account> --to <account> [--date YYYY-MM-DD] [--note <text>]",
+        ))));
+    };
+
+    let from = known_account(accounts, &from)?;
+    let to = known_account(accounts, &to)?;
+    if from == to {
+        return Err(BtrError::InvalidData(Some(String::from(
+            "A transfer needs two different accounts.",
+        ))));
+    }
+
+    let amount = args::parse_amount(amount)?;
+    if amount <= 0.0 {
+        return Err(BtrError::InvalidData(Some(String::from(
+            "The amount must be greater than 0.",
+        ))));
+    }
+
+    let date = match args::option_value(args, "--date") {
+        Some(date) => args::parse_date(date)?,
+        None => Utc::now().date_naive(),
+    };
+
+    let transfer =
+        TransferRecord::new(from, to, amount, date).with_note(args::option_text(args, "--note"));
+    let sheet_name = add_to_sheet(&mut cli.tracker_manager, date, |sheet| {
+        sheet.transfers_mut().push(transfer);
+    })?;
+
+    println!(
+        "!> Transfer added{}!",
+        added_to(&cli.tracker_manager, &sheet_name)
+    );
+
+    Ok(())
+}
+
+/* Balances of the accounts at the end of the period and the money moved within it. */
+pub fn show_accounts_handler(cli: &mut TrackerCli, args: &[&str]) -> Result<(), BtrError> {
+    let manager = &cli.tracker_manager;
+
+    let (label, period) = match args::option_value(args, "--period") {
+        Some(spec) => (spec.to_string(), manager.parse_period(spec)?),
+        None => match manager.get_active_sheet() {
+            Some(sheet) => (sheet.name.clone(), sheet.period.clone()),
+            None => (String::from("month"), Period::current_month()?),
+        },
+    };
+
+    let history = RecordQuery::new().range(Period::new(NaiveDate::MIN, *period.end())?);
+    let movements = accounts::account_movements(
+        manager.get_accounts(),
+        &period,
+        &manager.query(&history)?,
+        &manager.query_income(&history)?,
+        &manager.query_transfers(&history)?,
+    );
+
+    println!("\n{:<22} {}", "ACCOUNTS FOR", label);
+    println!("{:<22} {} - {}", "PERIOD", period.start(), period.end());
+    println!("{}\n", "-".repeat(72));
+    println!(
+        "{:<20} {:>12} {:>12} {:>12} {:>12}",
+        "Account", "Opening", "In", "Out", "Closing"
+    );
+    println!("{}", "-".repeat(72));
+
+    for movement in &movements {
+        println!(
+            "{:<20} {:>12.2} {:>12.2} {:>12.2} {:>12.2}",
+            movement.name,
+            movement.opening,
+            movement.inflow,
+            movement.outflow,
+            movement.closing()
+        );
+    }
+
+    let sum = |value: fn(&accounts::AccountMovement) -> f32| {
+        movements.iter().fold(0.0, |sum, m| sum + value(m))
+    };
+    println!("{}", "-".repeat(72));
+    println!(
+        "{:<20} {:>12.2} {:>12.2} {:>12.2} {:>12.2}",
+        "TOTAL",
+        sum(|m| m.opening),
+        sum(|m| m.inflow),
+        sum(|m| m.outflow),
+        sum(|m| m.closing())
+    );
+
+    Ok(())
+}
+
+/* The record of the reconciled account an entry stands for. */
+enum EntryLine {
+    Expense(ExpenseRecord),
+    Income(IncomeRecord),
+    TransferOut(TransferRecord),
+    TransferIn(TransferRecord),
+}
+
+struct StatementEntry {
+    line: EntryLine,
+    /* Which one of several identical records it is, as a sheet may repeat a record. */
+    occurrence: usize,
+    date: NaiveDate,
+    label: String,
+    /* Effect on the account balance, negative for money leaving it. */
+    amount: f32,
+    cleared: bool,
+}
+
+/*
+ * Every record of the account up to the statement date, from all sheets. Records copied to
+ * overlapping sheets are listed once, as in every other query.
+ */
+fn statement_entries(
+    manager: &TrackerManager,
+    account: &str,
+    until: NaiveDate,
+) -> Result<Vec<StatementEntry>, BtrError> {
+    let accounts = manager.get_accounts();
+    let is_account = |name: Option<&str>| accounts::resolve(accounts, name) == account;
+    let query = RecordQuery::new().range(Period::new(NaiveDate::MIN, until)?);
+
+    let mut entries = Vec::new();
+    let mut push = |line, date, label: String, amount, status: ClearStatus| {
+        entries.push(StatementEntry {
+            line,
+            occurrence: 0,
+            date,
+            label,
+            amount,
+            cleared: !status.is_pending(),
+        });
+    };
+
+    for record in manager.query(&query)? {
+        if is_account(record.account()) {
+            let label = format!(
+                "{} {}",
+                record.category(),
+                record.payee().or(record.note()).unwrap_or_default()
+            );
+            push(
+                EntryLine::Expense(record.clone()),
+                record.logged_on(),
+                label,
+                -record.amount(),
+                record.status(),
+            );
+        }
+    }
+    for record in manager.query_income(&query)? {
+        if is_account(record.account()) {
+            push(
+                EntryLine::Income(record.clone()),
+                record.logged_on(),
+                record.source().to_string(),
+                record.amount(),
+                record.status(),
+            );
+        }
+    }
+    for transfer in manager.query_transfers(&query)? {
+        if is_account(Some(transfer.from())) {
+            push(
+                EntryLine::TransferOut(transfer.clone()),
+                transfer.logged_on(),
+                format!("Transfer to {}", transfer.to()),
+                -transfer.amount(),
+                transfer.outgoing_status(),
+            );
+        }
+        if is_account(Some(transfer.to())) {
+            push(
+                EntryLine::TransferIn(transfer.clone()),
+                transfer.logged_on(),
+                format!("Transfer from {}", transfer.from()),
+                transfer.amount(),
+                transfer.incoming_status(),
+            );
+        }
+    }
+
+    for idx in 0..entries.len() {
+        entries[idx].occurrence = entries[..idx]
+            .iter()
+            .filter(|earlier| same_line(&earlier.line, &entries[idx].line))
+            .count();
+    }
+    entries.sort_by_key(|entry| entry.date);
+    Ok(entries)
+}
+
+fn same_line(a: &EntryLine, b: &EntryLine) -> bool {
+    match (a, b) {
+        (EntryLine::Expense(a), EntryLine::Expense(b)) => query::same_record(a, b),
+        (EntryLine::Income(a), EntryLine::Income(b)) => query::same_income(a, b),
+        (EntryLine::TransferOut(a), EntryLine::TransferOut(b))
+        | (EntryLine::TransferIn(a), EntryLine::TransferIn(b)) => query::same_transfer(a, b),
+        _ => false,
+    }
+}
+
+/* Sets the status of the record of the entry, returns whether the sheet holds a copy of it. */
+fn set_entry_status(sheet: &mut ExpenseSheet, entry: &StatementEntry, status: ClearStatus) -> bool {
+    let n = entry.occurrence;
+    match &entry.line {
+        EntryLine::Expense(record) => {
+            nth(sheet.expenses_mut(), |r| query::same_record(r, record), n)
+                .map(|r| r.set_status(status))
+        }
+        EntryLine::Income(record) => nth(sheet.income_mut(), |r| query::same_income(r, record), n)
+            .map(|r| r.set_status(status)),
+        EntryLine::TransferOut(transfer) => nth(
+            sheet.transfers_mut(),
+            |t| query::same_transfer(t, transfer),
+            n,
+        )
+        .map(|t| t.set_outgoing_status(status)),
+        EntryLine::TransferIn(transfer) => nth(
+            sheet.transfers_mut(),
+            |t| query::same_transfer(t, transfer),
+            n,
+        )
+        .map(|t| t.set_incoming_status(status)),
+    }
+    .is_some()
+}
+
+/* The n-th item the same as the searched one. */
+fn nth<T>(items: &mut [T], same: impl Fn(&T) -> bool, n: usize) -> Option<&mut T> {
+    items.iter_mut().filter(|item| same(item)).nth(n)
+}
+
+/* reconcile <account> [--balance <amount>] [--date YYYY-MM-DD] */
+pub fn reconcile_handler(cli: &mut TrackerCli, args: &[&str]) -> Result<(), BtrError> {
+    let manager = &mut cli.tracker_manager;
+
+    let name = args::positional(&args[1..]).join(" ");
+    if name.is_empty() {
+        return Err(BtrError::InvalidData(Some(String::from(
+            "Usage: reconcile <account> [--balance <amount>] [--date YYYY-MM-DD]",
+        ))));
+    }
+    let account = known_account(manager.get_accounts(), &name)?;
+    let opening = find_account(manager.get_accounts(), &account).map_or(0.0, |a| a.opening_balance);
+
+    let balance = match args::option_value(args, "--balance") {
+        Some(balance) => args::parse_amount(balance)?,
+        None => {
+            println!("!> Enter the statement end balance:");
+            loop {
+                match TrackerCli::user_input()?.trim().parse::<f32>() {
+                    Ok(balance) => break balance,
+                    Err(_) => println!("! Invalid input. Enter an amount."),
+                }
+            }
+        }
+    };
+
+    let date = match args::option_value(args, "--date") {
+        Some(date) => args::parse_date(date)?,
+        None => {
+            println!("!> Enter the statement date (YYYY-MM-DD, empty for today):");
+            loop {
+                match optional_input()? {
+                    None => break Utc::now().date_naive(),
+                    Some(date) => match args::parse_date(&date) {
+                        Ok(date) => break date,
+                        Err(e) => println!("! {}", e),
+                    },
+                }
+            }
+        }
+    };
+
+    let entries = statement_entries(manager, &account, date)?;
+
+    let cleared_before = entries
+        .iter()
+        .filter(|entry| entry.cleared)
+        .fold(opening, |sum, entry| sum + entry.amount);
+    let pending: Vec<&StatementEntry> = entries.iter().filter(|entry| !entry.cleared).collect();
+    let mut ticked = vec![false; pending.len()];
+
+    let cleared_balance = |ticked: &[bool]| {
+        pending
+            .iter()
+            .zip(ticked)
+            .filter(|(_, ticked)| **ticked)
+            .fold(cleared_before, |sum, (entry, _)| sum + entry.amount)
+    };
+
+    println!("\n{:<22} {}", "RECONCILING", account);
+    println!("{:<22} {:.2} PLN on {}", "STATEMENT BALANCE", balance, date);
+
+    if pending.is_empty() {
+        println!("{}", "-".repeat(72));
+        println!(
+            "> No pending records. Difference: {:+.2} PLN.",
+            balance - cleared_before
+        );
+        return Ok(());
+    }
+
+    loop {
+        println!("{}", "-".repeat(72));
+        for (idx, (entry, ticked)) in pending.iter().zip(&ticked).enumerate() {
+            println!(
+                "> {:>3}. [{}] {} {:<36} {:>+10.2}",
+                idx,
+                if *ticked { "x" } else { " " },
+                entry.date,
+                entry.label,
+                entry.amount
+            );
+        }
+        println!("{}", "-".repeat(72));
+
+        let cleared = cleared_balance(&ticked);
+        println!(
+            "{:<22} {:.2} PLN, difference {:+.2} PLN",
+            "CLEARED BALANCE",
+            cleared,
+            balance - cleared
+        );
+        println!("!> Enter numbers to tick or untick, 'all', or an empty line to finish:");
+
+        let Some(input) = optional_input()? else {
+            break;
+        };
+        if input.eq_ignore_ascii_case("all") {
+            ticked.iter_mut().for_each(|t| *t = true);
+            continue;
+        }
+        for token in input.split([' ', ',']).filter(|t| !t.is_empty()) {
+            match token.parse::<usize>() {
+                Ok(idx) if idx < ticked.len() => ticked[idx] = !ticked[idx],
+                _ => println!("! Skipped '{}', not one of the listed numbers.", token),
+            }
+        }
+    }
+
+    let count = ticked.iter().filter(|t| **t).count();
+    let difference = balance - cleared_balance(&ticked);
+    if difference.abs() < 0.005 {
+        println!("!> Account reconciled, the cleared balance matches the statement.");
+    } else {
+        println!("!> Difference of {:+.2} PLN remains.", difference);
+    }
+
+    if count == 0 || !confirm(&format!("Mark {} records as cleared?", count))? {
+        return Ok(());
+    }
+
+    /* Every copy of a record in overlapping sheets is cleared. */
+    for mut sheet in manager.load_sheets()? {
+        let mut changed = false;
+        for (entry, _) in pending.iter().zip(&ticked).filter(|(_, ticked)| **ticked) {
+            changed |= set_entry_status(&mut sheet, entry, ClearStatus::Cleared);
+        }
+        if changed {
+            manager.save_sheet(sheet)?;
+        }
+    }
+    println!("!> {} records marked as cleared.", count);
+
+    Ok(())
+}
@@ -0,0 +1,231 @@
+use super::{
+    account::account_from_args,
+    sheet::{add_to_sheet, added_to},
+};
+use crate::{
+    console::{args, cli::TrackerCli},
+    database::{
+        expense::{Debt, DebtKind, ExpenseRecord},
+        query::RecordQuery,
+    },
+    error::BtrError,
+    report::debts,
+};
+use chrono::Utc;
+
+/*
+ * add debt <lent|borrowed> <counterparty> <amount> [--interest <percent>] [--date YYYY-MM-DD]
+ *   [--note <text>] [--account <name>]
+ * The debt and the money it moved both go to the sheet 'record_sheet' chooses for the date.
+ */
+pub fn add_debt_handler(cli: &mut TrackerCli, args: &[&str]) -> Result<(), BtrError> {
+    let usage = || {
+        BtrError::InvalidData(Some(String::from(
+            "Usage: add debt <lent|borrowed> <counterparty> <amount> [--interest <percent>] [--date YYYY-MM-DD] [--note <text>] [--account <name>]",
+        )))
+    };
+
+    let [kind, counterparty @ .., amount] = args::positional(&args[2..]) else {
+        return Err(usage());
+    };
+    if counterparty.is_empty() {
+        return Err(usage());
+    }
+    let kind = match kind.to_ascii_lowercase().as_str() {
+        "lent" => DebtKind::Lent,
+        "borrowed" => DebtKind::Borrowed,
+        _ => return Err(usage()),
+    };
+
+    let amount = args::parse_amount(amount)?;
+    if amount <= 0.0 {
+        return Err(BtrError::InvalidData(Some(String::from(
+            "The amount must be greater than 0.",
+        ))));
+    }
+
+    let interest = args::option_value(args, "--interest")
+        .map(args::parse_amount)
+        .transpose()?;
+    if interest.is_some_and(|rate| rate < 0.0) {
+        return Err(BtrError::InvalidData(Some(String::from(
+            "The interest can't be negative.",
+        ))));
+    }
+
+    let date = match args::option_value(args, "--date") {
+        Some(date) => args::parse_date(date)?,
+        None => Utc::now().date_naive(),
+    };
+    let account = account_from_args(cli.tracker_manager.get_accounts(), args)?;
+
+    let manager = &mut cli.tracker_manager;
+    let id = manager
+        .query_debts(&RecordQuery::new())?
+        .iter()
+        .map(|debt| debt.id)
+        .max()
+        .map_or(1, |max| max + 1);
+    let debt = Debt {
+        id,
+        counterparty: counterparty.join(" "),
+        kind,
+        principal: amount,
+        interest,
+        logged_on: date,
+        note: args::option_text(args, "--note"),
+    };
+    let record = ExpenseRecord::debt_flow(&debt, amount * kind.outflow(), date)
+        .with_note(debt.note.clone())
+        .with_account(account);
+
+    let sheet_name = add_to_sheet(manager, date, |sheet| {
+        sheet.debts_mut().push(debt);
+        sheet.expenses_mut().push(record);
+    })?;
+    println!("!> Debt #{} added{}!", id, added_to(manager, &sheet_name));
+
+    Ok(())
+}
+
+/*
+ * add repayment <debt ID> [<amount>] [--date YYYY-MM-DD] [--note <text>] [--account <name>]
+ * Pays back a debt in either direction, the whole outstanding balance without an amount.
+ */
+pub fn add_repayment_handler(cli: &mut TrackerCli, args: &[&str]) -> Result<(), BtrError> {
+    let (id, amount) = match args::positional(&args[2..]) {
+        [id] => (id, None),
+        [id, amount] => (id, Some(args::parse_amount(amount)?)),
+        _ => {
+            return Err(BtrError::InvalidData(Some(String::from(
+                "Usage: add repayment <debt ID> [<amount>] [--date YYYY-MM-DD] [--note <text>] [--account <name>]",
+            ))));
+        }
+    };
+    let id = id
+        .trim_start_matches('#')
+        .parse::<u64>()
+        .map_err(|_| BtrError::InvalidData(Some(format!("'{}' is not a debt ID.", id))))?;
+
+    let date = match args::option_value(args, "--date") {
+        Some(date) => args::parse_date(date)?,
+        None => Utc::now().date_naive(),
+    };
+    let account = account_from_args(cli.tracker_manager.get_accounts(), args)?;
+
+    let manager = &mut cli.tracker_manager;
+    let everything = RecordQuery::new();
+    let balance = debts::debt_balances(
+        &manager.query_debts(&everything)?,
+        &manager.query(&everything)?,
+        date,
+    )
+    .into_iter()
+    .find(|balance| balance.debt.id == id)
+    .ok_or_else(|| BtrError::InvalidData(Some(format!("There is no debt #{}.", id))))?;
+
+    let outstanding = balance.outstanding();
+    if balance.is_settled() {
+        return Err(BtrError::InvalidData(Some(format!(
+            "Debt #{} has already been paid back.",
+            id
+        ))));
+    }
+    let amount = amount.unwrap_or(outstanding);
+    if amount <= 0.0 || amount > outstanding + 0.005 {
+        return Err(BtrError::InvalidData(Some(format!(
+            "The repayment must be greater than 0 and at most {:.2}.",
+            outstanding
+        ))));
+    }
+
+    let record =
+        ExpenseRecord::debt_flow(&balance.debt, -amount * balance.debt.kind.outflow(), date)
+            .with_note(args::option_text(args, "--note"))
+            .with_account(account);
+    let sheet_name = add_to_sheet(manager, date, |sheet| {
+        sheet.expenses_mut().push(record);
+    })?;
+    println!(
+        "!> Repayment of {:.2} PLN added{}, {:.2} PLN left.",
+        amount,
+        added_to(manager, &sheet_name),
+        outstanding - amount
+    );
+
+    Ok(())
+}
+
+/* show debts [--all] */
+pub fn show_debts_handler(cli: &mut TrackerCli, args: &[&str]) -> Result<(), BtrError> {
+    let today = Utc::now().date_naive();
+    let manager = &cli.tracker_manager;
+    let everything = RecordQuery::new();
+    let (settled, outstanding): (Vec<_>, Vec<_>) = debts::debt_balances(
+        &manager.query_debts(&everything)?,
+        &manager.query(&everything)?,
+        today,
+    )
+    .into_iter()
+    .partition(|balance| balance.is_settled());
+
+    let show_all = args::has_flag(args, "--all");
+    let listed: Vec<&debts::DebtBalance> = if show_all {
+        outstanding.iter().chain(&settled).collect()
+    } else {
+        outstanding.iter().collect()
+    };
+    if listed.is_empty() {
+        println!("> No outstanding debts.");
+        return Ok(());
+    }
+
+    println!("\n{:<22} {}", "DEBTS AS OF", today);
+    println!("{}\n", "-".repeat(92));
+    println!(
+        "{:>4}  {:<20} {:<9} {:>10} {:>9} {:>10} {:>11}  Since",
+        "ID", "Counterparty", "Kind", "Principal", "Interest", "Repaid", "Outstanding"
+    );
+    println!("{}", "-".repeat(92));
+
+    for balance in listed {
+        let debt = &balance.debt;
+        let kind = match debt.kind {
+            DebtKind::Lent => "lent",
+            DebtKind::Borrowed => "borrowed",
+        };
+        println!(
+            "{:>4}  {:<20} {:<9} {:>10.2} {:>9.2} {:>10.2} {:>11.2}  {}",
+            format!("#{}", debt.id),
+            debt.counterparty,
+            kind,
+            debt.principal,
+            balance.interest,
+            balance.repaid,
+            balance.outstanding().max(0.0),
+            debt.logged_on
+        );
+        for (date, amount) in &balance.repayments {
+            println!("{:<6} {} repaid {:.2}", "", date, amount);
+        }
+    }
+
+    let total = |kind: DebtKind| {
+        outstanding
+            .iter()
+            .filter(|balance| balance.debt.kind == kind)
+            .fold(0.0, |sum, balance| sum + balance.outstanding())
+    };
+    println!("{}", "-".repeat(92));
+    println!("{:<20} {:>10.2} PLN", "Owed to you", total(DebtKind::Lent));
+    println!("{:<20} {:>10.2} PLN", "You owe", total(DebtKind::Borrowed));
+
+    if !show_all && !settled.is_empty() {
+        println!(
+            "\n> {} debts paid back, use --all to list them.",
+            settled.len()
+        );
+    }
+
+    Ok(())
+}
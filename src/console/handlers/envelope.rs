@@ -0,0 +1,175 @@
+use super::{
+    expense::known_category,
+    sheet::{added_to, record_sheet},
+};
+use crate::{
+    console::{args, cli::TrackerCli},
+    database::{
+        expense::{Assignment, ExpenseSheet},
+        manager::TrackerManager,
+    },
+    error::BtrError,
+    report::envelopes::{self, EnvelopeBudget},
+};
+use chrono::Utc;
+
+pub fn show_envelopes_handler(cli: &mut TrackerCli, _args: &[&str]) -> Result<(), BtrError> {
+    let Some(budget) = active_envelopes(&cli.tracker_manager)? else {
+        return Err(envelopes_off());
+    };
+    let Some(sheet) = cli.tracker_manager.get_active_sheet() else {
+        return Err(BtrError::ActiveSheetNotSelected);
+    };
+
+    println!("\n{:<22} {}", "ENVELOPES FOR", sheet.name);
+    println!(
+        "{:<22} {} - {}",
+        "PERIOD",
+        sheet.period.start(),
+        sheet.period.end()
+    );
+    println!("{}\n", "-".repeat(72));
+    println!(
+        "{:<20} {:>12} {:>12} {:>12} {:>12}",
+        "Category", "Carried", "Assigned", "Spent", "Available"
+    );
+    println!("{}", "-".repeat(72));
+
+    let (mut carried, mut assigned, mut spent) = (0.0, 0.0, 0.0);
+    for envelope in &budget.envelopes {
+        let overspent = if envelope.available() < -0.005 {
+            "  overspent"
+        } else {
+            ""
+        };
+        println!(
+            "{:<20} {:>12.2} {:>12.2} {:>12.2} {:>12.2}{}",
+            envelope.category,
+            envelope.carried,
+            envelope.assigned,
+            envelope.spent,
+            envelope.available(),
+            overspent
+        );
+        carried += envelope.carried;
+        assigned += envelope.assigned;
+        spent += envelope.spent;
+    }
+    println!("{}", "-".repeat(72));
+    println!(
+        "{:<20} {:>12.2} {:>12.2} {:>12.2} {:>12.2}",
+        "TOTAL",
+        carried,
+        assigned,
+        spent,
+        carried + assigned - spent
+    );
+    println!("\n{:<20} {:>12.2} PLN", "Income", budget.income);
+    println!("{:<20} {:>12.2} PLN", "To be assigned", budget.to_assign);
+
+    Ok(())
+}
+
+/* Envelopes of the active sheet, None when envelope budgeting is off. */
+fn active_envelopes(manager: &TrackerManager) -> Result<Option<EnvelopeBudget>, BtrError> {
+    match manager.get_active_sheet() {
+        Some(sheet) => sheet_envelopes(manager, sheet),
+        None if manager.get_envelope_mode().is_none() => Ok(None),
+        None => Err(BtrError::ActiveSheetNotSelected),
+    }
+}
+
+pub(super) fn sheet_envelopes(
+    manager: &TrackerManager,
+    sheet: &ExpenseSheet,
+) -> Result<Option<EnvelopeBudget>, BtrError> {
+    let Some(mode) = manager.get_envelope_mode() else {
+        return Ok(None);
+    };
+
+    let mut chain = manager.sheet_chain(sheet)?;
+    if let Some(since) = mode.since {
+        chain.retain(|sheet| *sheet.period.end() >= since);
+    }
+    Ok(Some(envelopes::envelopes(
+        &chain,
+        manager.get_categories(),
+        mode.leftover,
+    )))
+}
+
+fn envelopes_off() -> BtrError {
+    BtrError::InvalidData(Some(String::from(
+        "Envelope budgeting is off. Enable it with an [envelopes] table in the expenses configuration.",
+    )))
+}
+
+/*
+ * assign <category> <amount> [--date YYYY-MM-DD]
+ * Puts income into the envelope of a category, a negative amount takes money out of it. The
+ * assignment goes to the sheet 'record_sheet' chooses for the date.
+ */
+pub fn assign_handler(cli: &mut TrackerCli, args: &[&str]) -> Result<(), BtrError> {
+    let Some((amount, category)) = args::positional(&args[1..]).split_last() else {
+        return Err(BtrError::InvalidData(Some(String::from(
+            "Usage: assign <category> <amount> [--date YYYY-MM-DD]",
+        ))));
+    };
+    let category = known_category(cli.tracker_manager.get_categories(), &category.join(" "))?
+        .name
+        .clone();
+
+    let amount = args::parse_amount(amount)?;
+    if amount.abs() < 0.005 {
+        return Err(BtrError::InvalidData(Some(String::from(
+            "The amount can't be 0.",
+        ))));
+    }
+
+    let date = match args::option_value(args, "--date") {
+        Some(date) => args::parse_date(date)?,
+        None => Utc::now().date_naive(),
+    };
+    if cli.tracker_manager.get_envelope_mode().is_none() {
+        return Err(envelopes_off());
+    }
+
+    let sheet_name = record_sheet(&mut cli.tracker_manager, date)?;
+    let mut sheet = TrackerManager::load_sheet(&sheet_name)?;
+    let Some(budget) = sheet_envelopes(&cli.tracker_manager, &sheet)? else {
+        return Err(envelopes_off());
+    };
+    let available = budget.envelope(&category).map_or(0.0, |e| e.available());
+    if amount > budget.to_assign + 0.005 {
+        return Err(BtrError::InvalidData(Some(format!(
+            "Only {:.2} PLN is left to assign.",
+            budget.to_assign
+        ))));
+    }
+    if amount < 0.0 && -amount > available + 0.005 {
+        return Err(BtrError::InvalidData(Some(format!(
+            "Envelope '{}' holds only {:.2} PLN.",
+            category, available
+        ))));
+    }
+
+    let assignment = Assignment {
+        category: category.clone(),
+        amount,
+        logged_on: date,
+    };
+    sheet.assignments_mut().push(assignment);
+    cli.tracker_manager.save_sheet(sheet)?;
+    println!(
+        "!> Assignment added{}!",
+        added_to(&cli.tracker_manager, &sheet_name)
+    );
+    println!(
+        "!> Envelope '{}' holds {:.2} PLN, {:.2} PLN left to assign.",
+        category,
+        available + amount,
+        budget.to_assign - amount
+    );
+
+    Ok(())
+}
@@ -0,0 +1,653 @@
+use super::{
+    account::{account_from_args, account_from_prompt},
+    confirm,
+    envelope::sheet_envelopes,
+    filter_from_args, optional_input, print_records, record_line,
+    shared::{shared_from_args, shared_from_prompt},
+    sheet::{add_to_sheet, added_to, record_sheet, roll_over},
+};
+use crate::{
+    console::{args, cli::TrackerCli},
+    database::{
+        config::Routing,
+        expense::{ExpenseCategory, ExpenseRecord, ExpenseSheet, SplitLine},
+        manager::TrackerManager,
+        query::RecordQuery,
+    },
+    error::BtrError,
+    report::anomaly,
+};
+use chrono::{NaiveDate, Utc};
+
+pub fn add_expense_handler(cli: &mut TrackerCli, args: &[&str]) -> Result<(), BtrError> {
+    roll_over(cli)?;
+
+    let accounts = cli.tracker_manager.get_accounts();
+    let people = cli.tracker_manager.get_people();
+    /* Without arguments, or with '--details' only, the expense is entered step by step. */
+    let details = args[2..] == ["--details"];
+    let new_expense = if args.len() > 2 && !details {
+        let record = expense_from_args(cli.tracker_manager.get_categories(), args)?
+            .with_account(account_from_args(accounts, args)?);
+        let shared = shared_from_args(people, args)?;
+        if let Some(shared) = &shared {
+            shared.validate(record.amount())?;
+        }
+        record.with_shared(shared)
+    } else if details {
+        let record = expense_from_prompt(cli.tracker_manager.get_categories(), true)?
+            .with_account(account_from_prompt(accounts)?);
+        let shared = shared_from_prompt(people, record.amount())?;
+        record.with_shared(shared)
+    } else {
+        expense_from_prompt(cli.tracker_manager.get_categories(), false)?
+    };
+
+    let sheet_name = record_sheet(&mut cli.tracker_manager, new_expense.logged_on())?;
+    let mut sheet = TrackerManager::load_sheet(&sheet_name)?;
+
+    /* With envelope budgeting the expense has to fit in what is left in its envelopes. */
+    let left: Vec<(String, f32)> = match sheet_envelopes(&cli.tracker_manager, &sheet)? {
+        Some(budget) => new_expense
+            .parts()
+            .into_iter()
+            .map(|(category, amount)| {
+                let available = budget.envelope(category).map_or(0.0, |e| e.available());
+                (category.to_string(), available - amount)
+            })
+            .collect(),
+        None => Vec::new(),
+    };
+    let short: Vec<String> = left
+        .iter()
+        .filter(|(_, left)| *left < -0.005)
+        .map(|(category, left)| format!("'{}' is short by {:.2} PLN", category, -left))
+        .collect();
+    if !short.is_empty()
+        && !confirm(&format!(
+            "Envelope {}. Add the expense anyway?",
+            short.join(", ")
+        ))?
+    {
+        return Ok(());
+    }
+
+    let history = cli.tracker_manager.query(&RecordQuery::new())?;
+    let mut anomalies =
+        anomaly::check_record(&new_expense, &history, cli.tracker_manager.get_categories());
+
+    /* A jump is reported only by the record taking the total of its category over the limit. */
+    let jumps = |sheet: &ExpenseSheet| {
+        anomaly::check_monthly_jumps(
+            &sheet.period,
+            sheet.expenses(),
+            &history,
+            cli.tracker_manager.get_categories(),
+        )
+    };
+    let jumped_before = jumps(&sheet);
+    let categories: Vec<String> = new_expense
+        .parts()
+        .iter()
+        .map(|(category, _)| category.to_string())
+        .collect();
+    sheet.expenses_mut().push(new_expense);
+    anomalies.extend(jumps(&sheet).into_iter().filter(|a| {
+        categories.contains(&a.category) && !jumped_before.iter().any(|b| b.category == a.category)
+    }));
+    cli.tracker_manager.save_sheet(sheet)?;
+
+    println!(
+        "!> Expense added{}!",
+        added_to(&cli.tracker_manager, &sheet_name)
+    );
+    for (category, left) in &left {
+        println!("!> Envelope '{}': {:.2} PLN left.", category, left);
+    }
+
+    for anomaly in anomalies {
+        println!("!> Unusual: {}.", anomaly.describe());
+    }
+
+    Ok(())
+}
+
+/*
+ * One-line form: add expense <category> <amount> [--date YYYY-MM-DD] [--payee <payee>]
+ * [--note <text>] [--tags <tag,tag>] [--account <name>] [--paid-by <person>]
+ * [--split <split>]
+ */
+fn expense_from_args(
+    categories: &[ExpenseCategory],
+    args: &[&str],
+) -> Result<ExpenseRecord, BtrError> {
+    let usage = || {
+        BtrError::InvalidData(Some(String::from(
+            "Usage: add expense <category> <amount> [--date YYYY-MM-DD] [--payee <payee>] [--note <text>] [--tags <tag,tag>] [--account <name>] [--paid-by <person>] [--split <split>], or add expense <total> --line <category> <amount> [note] --line ...",
+        )))
+    };
+
+    let Some((amount, category)) = args::positional(&args[2..]).split_last() else {
+        return Err(usage());
+    };
+    let lines = split_lines_from_args(categories, args)?;
+    /* A split expense takes the categories from its lines. */
+    if category.is_empty() == lines.is_empty() {
+        return Err(usage());
+    }
+
+    let amount = args::parse_amount(amount)?;
+    if amount <= 0.0 {
+        return Err(BtrError::InvalidData(Some(String::from(
+            "The amount must be greater than 0.",
+        ))));
+    }
+
+    let date = match args::option_value(args, "--date") {
+        Some(date) => args::parse_date(date)?,
+        None => Utc::now().date_naive(),
+    };
+
+    let record = if lines.is_empty() {
+        let category = known_category(categories, &category.join(" "))?;
+        ExpenseRecord::new(category.name.clone(), amount, date)
+    } else {
+        split_expense(lines, amount, date)?
+    };
+
+    Ok(record
+        .with_payee(args::option_text(args, "--payee"))
+        .with_note(args::option_text(args, "--note"))
+        .with_tags(split_tags(
+            &args::option_text(args, "--tags").unwrap_or_default(),
+        )))
+}
+
+pub(super) fn known_category<'a>(
+    categories: &'a [ExpenseCategory],
+    name: &str,
+) -> Result<&'a ExpenseCategory, BtrError> {
+    categories
+        .iter()
+        .find(|c| c.name.eq_ignore_ascii_case(name))
+        .ok_or_else(|| BtrError::InvalidData(Some(format!("Unknown category '{}'.", name))))
+}
+
+/* Split expense whose lines have to add up to the total given. */
+fn split_expense(
+    lines: Vec<SplitLine>,
+    total: f32,
+    date: NaiveDate,
+) -> Result<ExpenseRecord, BtrError> {
+    let record = ExpenseRecord::split(lines, date)?;
+    if (record.amount() - total).abs() > 0.005 {
+        return Err(BtrError::InvalidData(Some(format!(
+            "The lines sum up to {:.2}, not {:.2}.",
+            record.amount(),
+            total
+        ))));
+    }
+
+    Ok(record)
+}
+
+/* Every '--line <category> <amount> [note]' given. */
+fn split_lines_from_args(
+    categories: &[ExpenseCategory],
+    args: &[&str],
+) -> Result<Vec<SplitLine>, BtrError> {
+    args.iter()
+        .enumerate()
+        .filter(|(_, arg)| **arg == "--line")
+        .map(|(pos, _)| {
+            let tokens: Vec<&str> = args[pos + 1..]
+                .iter()
+                .take_while(|arg| !arg.starts_with("--"))
+                .copied()
+                .collect();
+            split_line(&tokens, |name| {
+                known_category(categories, name).map(|c| c.name.clone())
+            })
+        })
+        .collect()
+}
+
+/* Parses '<category> <amount> [note]' tokens of a split line, the amount ends the category. */
+fn split_line(
+    tokens: &[&str],
+    category: impl Fn(&str) -> Result<String, BtrError>,
+) -> Result<SplitLine, BtrError> {
+    let amount_pos = match tokens.iter().skip(1).position(|t| t.parse::<f32>().is_ok()) {
+        Some(pos) => pos + 1,
+        None => {
+            return Err(BtrError::InvalidData(Some(format!(
+                "'{}' is not a split line. Expected <category> <amount> [note].",
+                tokens.join(" ")
+            ))));
+        }
+    };
+
+    let note = tokens[amount_pos + 1..].join(" ");
+    Ok(SplitLine {
+        category: category(&tokens[..amount_pos].join(" "))?,
+        amount: args::parse_amount(tokens[amount_pos])?,
+        note: (!note.is_empty()).then_some(note),
+    })
+}
+
+/* Asks for a category and an amount, with 'details' also for a split, a payee, a note and tags. */
+fn expense_from_prompt(
+    categories: &[ExpenseCategory],
+    details: bool,
+) -> Result<ExpenseRecord, BtrError> {
+    println!("!> Select a category:");
+    for (idx, category) in categories.iter().enumerate() {
+        println!("> {}: {}", idx + 1, category.name);
+    }
+
+    let category_idx = loop {
+        let input = TrackerCli::user_input()?;
+        match input.trim().parse::<usize>() {
+            Ok(idx) if idx >= 1 && idx <= categories.len() => break idx - 1,
+            _ => println!(
+                "! Invalid input. Select a number between 1 and {}.",
+                categories.len()
+            ),
+        }
+    };
+
+    println!("!> Enter amount:");
+    let amount = loop {
+        let input = TrackerCli::user_input()?;
+        match input.trim().parse::<f32>() {
+            Ok(num) if num > 0.0 => break num,
+            _ => println!("! Invalid input. The value must be greated then 0."),
+        }
+    };
+
+    /* Use the clone() for now. However you might consider a better solution there. */
+    let record = ExpenseRecord::new(
+        categories[category_idx].name.clone(),
+        amount,
+        Utc::now().date_naive(),
+    );
+    if !details {
+        return Ok(record);
+    }
+
+    let record = split_from_prompt(categories, amount)?.unwrap_or(record);
+
+    println!("!> Enter payee (optional):");
+    let payee = optional_input()?;
+
+    println!("!> Enter note (optional):");
+    let note = optional_input()?;
+
+    println!("!> Enter tags separated by commas (optional):");
+    let tags = split_tags(&optional_input()?.unwrap_or_default());
+
+    Ok(record.with_payee(payee).with_note(note).with_tags(tags))
+}
+
+/* Optional split of the amount into lines of different categories. */
+fn split_from_prompt(
+    categories: &[ExpenseCategory],
+    amount: f32,
+) -> Result<Option<ExpenseRecord>, BtrError> {
+    println!(
+        "!> Split into lines as '<category number> <amount> [note]', an empty line to finish (optional):"
+    );
+
+    let category = |number: &str| match number.parse::<usize>() {
+        Ok(idx) if idx >= 1 && idx <= categories.len() => Ok(categories[idx - 1].name.clone()),
+        _ => Err(BtrError::InvalidData(Some(format!(
+            "Select a category number between 1 and {}.",
+            categories.len()
+        )))),
+    };
+
+    let mut lines = Vec::new();
+    loop {
+        let Some(input) = optional_input()? else {
+            if lines.is_empty() {
+                return Ok(None);
+            }
+            match split_expense(std::mem::take(&mut lines), amount, Utc::now().date_naive()) {
+                Ok(record) => return Ok(Some(record)),
+                Err(e) => {
+                    println!("! {} Enter the lines again.", e);
+                    continue;
+                }
+            }
+        };
+
+        let tokens: Vec<&str> = input.split_ascii_whitespace().collect();
+        match split_line(&tokens, category) {
+            Ok(line) => lines.push(line),
+            Err(e) => println!("! {}", e),
+        }
+    }
+}
+
+fn split_tags(tags: &str) -> Vec<String> {
+    tags.split(',')
+        .map(str::trim)
+        .filter(|tag| !tag.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/* Amount already returned for the expense with the given ID. */
+fn refunded_amount(sheets: &[ExpenseSheet], id: u64) -> f32 {
+    sheets
+        .iter()
+        .flat_map(|sheet| sheet.expenses())
+        .filter(|record| record.refund_of() == Some(id))
+        .fold(0.0, |sum, record| sum - record.amount())
+}
+
+/*
+ * add refund [<amount>] [--date YYYY-MM-DD] [--note <text>] [query]
+ * The refund goes to the sheet 'record_sheet' chooses for its date and may refer to an
+ * expense of any earlier sheet.
+ */
+pub fn add_refund_handler(cli: &mut TrackerCli, args: &[&str]) -> Result<(), BtrError> {
+    let manager = &mut cli.tracker_manager;
+    if manager.get_routing() == Routing::Active && manager.get_active_sheet().is_none() {
+        return Err(BtrError::ActiveSheetNotSelected);
+    }
+
+    let amount = args
+        .get(2)
+        .filter(|arg| !arg.starts_with("--"))
+        .and_then(|arg| arg.parse::<f32>().ok());
+    let date = match args::option_value(args, "--date") {
+        Some(date) => args::parse_date(date)?,
+        None => Utc::now().date_naive(),
+    };
+    let filter = filter_from_args(args)?;
+
+    let mut sheets = manager.load_sheets()?;
+
+    /* Latest expenses first, only the most recent ones without a query. */
+    let mut listed: Vec<(usize, usize)> = sheets
+        .iter()
+        .enumerate()
+        .flat_map(|(sheet_idx, sheet)| {
+            sheet
+                .expenses()
+                .iter()
+                .enumerate()
+                .filter(|(_, record)| {
+                    !record.is_refund()
+                        && !record.is_debt()
+                        && record.logged_on() <= date
+                        && filter.as_ref().is_none_or(|f| f.matches(record))
+                })
+                .map(move |(record_idx, _)| (sheet_idx, record_idx))
+        })
+        .collect();
+    listed.sort_by_key(|&(sheet_idx, record_idx)| {
+        std::cmp::Reverse(sheets[sheet_idx].expenses()[record_idx].logged_on())
+    });
+    listed.truncate(filter.as_ref().and_then(|f| f.limit()).unwrap_or(20));
+
+    if listed.is_empty() {
+        println!("!> No expenses to refund.");
+        return Ok(());
+    }
+
+    println!("?> Select the refunded expense:");
+    for (idx, &(sheet_idx, record_idx)) in listed.iter().enumerate() {
+        let record = &sheets[sheet_idx].expenses()[record_idx];
+        let refunded = record.id().map_or(0.0, |id| refunded_amount(&sheets, id));
+        let refunded = if refunded > 0.0 {
+            format!(" (refunded {:.2})", refunded)
+        } else {
+            String::new()
+        };
+        println!("> {:>3}. {}{}", idx, record_line(record), refunded);
+    }
+
+    let (sheet_idx, record_idx) = loop {
+        match TrackerCli::user_input()?.trim().parse::<usize>() {
+            Ok(idx) if idx < listed.len() => break listed[idx],
+            _ => println!("> Invalid input. Enter one of the listed numbers."),
+        }
+    };
+
+    let original = sheets[sheet_idx].expenses()[record_idx].clone();
+    let remaining =
+        original.amount() - original.id().map_or(0.0, |id| refunded_amount(&sheets, id));
+    if remaining <= 0.005 {
+        return Err(BtrError::InvalidData(Some(String::from(
+            "The expense has already been refunded in full.",
+        ))));
+    }
+
+    let amount = match amount {
+        Some(amount) => amount,
+        None => {
+            println!(
+                "!> Enter the refunded amount (empty for the remaining {:.2}):",
+                remaining
+            );
+            loop {
+                let Some(input) = optional_input()? else {
+                    break remaining;
+                };
+                match input.parse::<f32>() {
+                    Ok(amount) => break amount,
+                    Err(_) => println!("! Invalid input. Enter an amount."),
+                }
+            }
+        }
+    };
+    if amount <= 0.0 || amount > remaining + 0.005 {
+        return Err(BtrError::InvalidData(Some(format!(
+            "The refund must be greater than 0 and at most {:.2}.",
+            remaining
+        ))));
+    }
+
+    /* Expenses get an ID the first time they are refunded. */
+    let id = match original.id() {
+        Some(id) => id,
+        None => {
+            let id = sheets
+                .iter()
+                .flat_map(|sheet| sheet.expenses())
+                .filter_map(|record| record.id())
+                .max()
+                .map_or(1, |max| max + 1);
+            sheets[sheet_idx].expenses_mut()[record_idx].set_id(id);
+            manager.save_sheet(sheets.swap_remove(sheet_idx))?;
+            id
+        }
+    };
+
+    let refund = ExpenseRecord::refund(&original, id, amount, date)
+        .with_note(args::option_text(args, "--note"));
+    let sheet_name = add_to_sheet(manager, date, |sheet| {
+        sheet.expenses_mut().push(refund);
+    })?;
+    println!(
+        "!> Refund of {:.2} PLN added{}!",
+        amount,
+        added_to(manager, &sheet_name)
+    );
+
+    Ok(())
+}
+
+pub fn show_categories_handler(cli: &mut TrackerCli, _args: &[&str]) -> Result<(), BtrError> {
+    println!("? CATEGORIES:");
+    for category in cli.tracker_manager.get_categories() {
+        println!(">  {}", category.name);
+        if let Some(description) = &category.description {
+            println!("   {}", description);
+        }
+    }
+
+    Ok(())
+}
+
+pub fn delete_expense_handler(cli: &mut TrackerCli, args: &[&str]) -> Result<(), BtrError> {
+    let Some(active_sheet) = cli.tracker_manager.get_active_sheet() else {
+        return Err(BtrError::ActiveSheetNotSelected);
+    };
+
+    /*
+     * Indices refer to the full sheet, a query only narrows down the list. Money of debts is
+     * not listed, it belongs to the debt.
+     */
+    let mut listed: Vec<(usize, &ExpenseRecord)> = active_sheet
+        .expenses()
+        .iter()
+        .enumerate()
+        .filter(|(_, record)| !record.is_debt())
+        .collect();
+    if let Some(filter) = filter_from_args(args)? {
+        listed.retain(|(_, record)| filter.matches(record));
+        listed.sort_by(|(_, a), (_, b)| filter.compare(a, b));
+        if let Some(limit) = filter.limit() {
+            listed.truncate(limit);
+        }
+    }
+
+    if listed.is_empty() {
+        println!("!> No expenses to delete.");
+        return Ok(());
+    }
+
+    println!("?> Select an expense to be deleted:");
+    print_records(listed.iter().copied());
+
+    let choise = loop {
+        let user_input = TrackerCli::user_input()?;
+
+        match user_input.trim().parse::<usize>() {
+            Ok(input) if listed.iter().any(|(idx, _)| *idx == input) => break input,
+            _ => {
+                println!("> Invalid input. Enter one of the listed numbers.")
+            }
+        }
+    };
+
+    /* Refunds of the expense would refer to nothing, they are removed along with it. */
+    let manager = &mut cli.tracker_manager;
+    let id = manager
+        .get_active_sheet()
+        .as_ref()
+        .and_then(|sheet| sheet.expenses()[choise].id());
+    let refunded_in: Vec<ExpenseSheet> = match id {
+        Some(id) => manager
+            .load_sheets()?
+            .into_iter()
+            .filter(|sheet| sheet.expenses().iter().any(|r| r.refund_of() == Some(id)))
+            .collect(),
+        None => Vec::new(),
+    };
+    if !refunded_in.is_empty() {
+        println!("?> Refunds of the expense:");
+        for sheet in &refunded_in {
+            for record in sheet.expenses().iter().filter(|r| r.refund_of() == id) {
+                println!("> [{}] {}", sheet.name, record_line(record));
+            }
+        }
+        if !confirm("Delete the expense together with its refunds?")? {
+            return Ok(());
+        }
+    }
+
+    manager.update_active_sheet(|sheet| {
+        sheet.expenses_mut().remove(choise);
+    })?;
+    for sheet in refunded_in {
+        let mut sheet = match manager.get_active_sheet() {
+            Some(active) if active.name == sheet.name => active.clone(),
+            _ => sheet,
+        };
+        sheet.expenses_mut().retain(|r| r.refund_of() != id);
+        manager.save_sheet(sheet)?;
+    }
+    println!("!> Expense has been sucesfully removed.");
+
+    Ok(())
+}
+
+/*
+ * Removes every record matching the query, across all sheets when the query is dated. Money
+ * of debts is left out, it belongs to the debt.
+ */
+pub fn delete_expenses_handler(cli: &mut TrackerCli, args: &[&str]) -> Result<(), BtrError> {
+    let Some(filter) = filter_from_args(args)? else {
+        return Err(BtrError::InvalidData(Some(String::from(
+            "A query is required, e.g. 'delete expenses where category = Cafe since 2025-01-01'.",
+        ))));
+    };
+
+    let manager = &mut cli.tracker_manager;
+    let mut sheets = if filter.since().is_some() || filter.until().is_some() {
+        manager.load_sheets()?
+    } else {
+        match manager.get_active_sheet() {
+            Some(sheet) => vec![sheet.clone()],
+            None => return Err(BtrError::ActiveSheetNotSelected),
+        }
+    };
+
+    let mut matched: Vec<(usize, usize)> = sheets
+        .iter()
+        .enumerate()
+        .flat_map(|(sheet_idx, sheet)| {
+            sheet
+                .expenses()
+                .iter()
+                .enumerate()
+                .filter(|(_, record)| !record.is_debt() && filter.matches(record))
+                .map(move |(record_idx, _)| (sheet_idx, record_idx))
+        })
+        .collect();
+
+    let record =
+        |&(sheet_idx, record_idx): &(usize, usize)| &sheets[sheet_idx].expenses()[record_idx];
+    matched.sort_by(|a, b| filter.compare(record(a), record(b)));
+    if let Some(limit) = filter.limit() {
+        matched.truncate(limit);
+    }
+
+    if matched.is_empty() {
+        println!("!> No expenses match the query.");
+        return Ok(());
+    }
+
+    println!("?> Expenses to be deleted:");
+    for entry in &matched {
+        println!(
+            "> [{}] {}",
+            sheets[entry.0].name,
+            record_line(record(entry))
+        );
+    }
+
+    if !confirm(&format!("Delete {} expenses?", matched.len()))? {
+        return Ok(());
+    }
+
+    /* Remove from the back, so the remaining indices stay valid. */
+    matched.sort_unstable_by(|a, b| b.cmp(a));
+    for (sheet_idx, record_idx) in &matched {
+        sheets[*sheet_idx].expenses_mut().remove(*record_idx);
+    }
+
+    let mut touched: Vec<usize> = matched.iter().map(|(sheet_idx, _)| *sheet_idx).collect();
+    touched.dedup();
+    for sheet_idx in touched {
+        manager.save_sheet(sheets[sheet_idx].clone())?;
+    }
+
+    println!("!> {} expenses have been removed.", matched.len());
+
+    Ok(())
+}
@@ -0,0 +1,56 @@
+use super::{file_label, output_path, report_scope};
+use crate::{console::cli::TrackerCli, database::expense::ExpenseRecord, error::BtrError};
+use std::fs;
+
+pub fn export_csv_handler(cli: &mut TrackerCli, args: &[&str]) -> Result<(), BtrError> {
+    let scope = report_scope(&cli.tracker_manager, args)?;
+
+    let mut csv = String::from("date,category,amount,payee,note,tags\n");
+    /* Split expenses take a row per line, as they are summed up per category. */
+    for record in ExpenseRecord::itemize(&scope.records) {
+        csv.push_str(&format!(
+            "{},{},{:.2},{},{},{}\n",
+            record.logged_on(),
+            csv_field(record.category()),
+            record.amount(),
+            csv_field(record.payee().unwrap_or_default()),
+            csv_field(record.note().unwrap_or_default()),
+            csv_field(&record.tags().join(";"))
+        ));
+    }
+
+    let out_path = output_path(args, &format!("export-{}.csv", file_label(&scope.label)))?;
+    fs::write(&out_path, csv)?;
+    println!(
+        "!> {} records exported to '{}'.",
+        scope.records.len(),
+        out_path.display()
+    );
+
+    Ok(())
+}
+
+pub fn export_json_handler(cli: &mut TrackerCli, args: &[&str]) -> Result<(), BtrError> {
+    let scope = report_scope(&cli.tracker_manager, args)?;
+
+    let json = serde_json::to_string_pretty(&ExpenseRecord::itemize(&scope.records))
+        .map_err(|e| BtrError::InvalidData(Some(format!("Failed to serialize the data: {}", e))))?;
+
+    let out_path = output_path(args, &format!("export-{}.json", file_label(&scope.label)))?;
+    fs::write(&out_path, json)?;
+    println!(
+        "!> {} records exported to '{}'.",
+        scope.records.len(),
+        out_path.display()
+    );
+
+    Ok(())
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
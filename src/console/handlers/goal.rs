@@ -0,0 +1,192 @@
+use super::confirm;
+use crate::{
+    console::{args, cli::TrackerCli},
+    database::config::{
+        Goal,
+        goal::{Contribution, find_goal},
+    },
+    error::BtrError,
+};
+use chrono::Utc;
+
+/* add goal <name> <target> --by <YYYY-MM|YYYY-MM-DD> */
+pub fn add_goal_handler(cli: &mut TrackerCli, args: &[&str]) -> Result<(), BtrError> {
+    let usage = || {
+        BtrError::InvalidData(Some(String::from(
+            "Usage: add goal <name> <target> --by <YYYY-MM|YYYY-MM-DD>",
+        )))
+    };
+
+    let (Some((target, name)), Some(deadline)) = (
+        args::positional(&args[2..]).split_last(),
+        args::option_value(args, "--by"),
+    ) else {
+        return Err(usage());
+    };
+    if name.is_empty() {
+        return Err(usage());
+    }
+    let name = name.join(" ");
+
+    let target = args::parse_amount(target)?;
+    if target <= 0.0 {
+        return Err(BtrError::InvalidData(Some(String::from(
+            "The target must be greater than 0.",
+        ))));
+    }
+
+    /* A month or a year means its last day. */
+    let deadline = match args::parse_date(deadline) {
+        Ok(date) => date,
+        Err(e) => match cli.tracker_manager.parse_period(deadline) {
+            Ok(period) => *period.end(),
+            Err(_) => return Err(e),
+        },
+    };
+    let today = Utc::now().date_naive();
+    if deadline < today {
+        return Err(BtrError::InvalidData(Some(format!(
+            "The deadline {} has already passed.",
+            deadline
+        ))));
+    }
+
+    let manager = &mut cli.tracker_manager;
+    if find_goal(manager.get_goals(), &name).is_some() {
+        return Err(BtrError::InvalidData(Some(format!(
+            "Goal '{}' already exists.",
+            name
+        ))));
+    }
+
+    manager.update_goals(|goals| goals.push(Goal::new(name, target, deadline, today)))?;
+    println!("!> Goal added!");
+
+    Ok(())
+}
+
+/* add contribution <goal> <amount> [--date YYYY-MM-DD] */
+pub fn add_contribution_handler(cli: &mut TrackerCli, args: &[&str]) -> Result<(), BtrError> {
+    let Some((amount, name)) = args::positional(&args[2..]).split_last() else {
+        return Err(BtrError::InvalidData(Some(String::from(
+            "Usage: add contribution <goal> <amount> [--date YYYY-MM-DD]",
+        ))));
+    };
+    let name = name.join(" ");
+
+    let amount = args::parse_amount(amount)?;
+    if amount <= 0.0 {
+        return Err(BtrError::InvalidData(Some(String::from(
+            "The amount must be greater than 0.",
+        ))));
+    }
+
+    let date = match args::option_value(args, "--date") {
+        Some(date) => args::parse_date(date)?,
+        None => Utc::now().date_naive(),
+    };
+
+    let manager = &mut cli.tracker_manager;
+    let Some(goal) = find_goal(manager.get_goals(), &name) else {
+        return Err(BtrError::InvalidData(Some(format!(
+            "Unknown goal '{}'.",
+            name
+        ))));
+    };
+    let mut updated = goal.clone();
+    updated.contributions.push(Contribution {
+        amount,
+        logged_on: date,
+    });
+    let added = format!(
+        "!> Contribution added, '{}' is at {:.2} of {:.2} PLN ({:.1}%).",
+        updated.name,
+        updated.saved(),
+        updated.target,
+        updated.progress()
+    );
+
+    manager.update_goals(|goals| {
+        if let Some(goal) = goals.iter_mut().find(|goal| goal.name == updated.name) {
+            *goal = updated;
+        }
+    })?;
+    println!("{}", added);
+
+    Ok(())
+}
+
+pub fn show_goals_handler(cli: &mut TrackerCli, _args: &[&str]) -> Result<(), BtrError> {
+    let goals = cli.tracker_manager.get_goals();
+    if goals.is_empty() {
+        println!("> No goals defined. Use 'add goal <name> <target> --by <YYYY-MM>'.");
+        return Ok(());
+    }
+
+    let today = Utc::now().date_naive();
+    println!("\n{:<22} {}", "SAVINGS GOALS AS OF", today);
+    println!("{}\n", "-".repeat(96));
+    println!(
+        "{:<20} {:>10} {:>10} {:>8}  {:<11} {:>12}  Projected",
+        "Goal", "Target", "Saved", "Progress", "Deadline", "Monthly need"
+    );
+    println!("{}", "-".repeat(96));
+
+    for goal in goals {
+        let monthly = if goal.is_reached() {
+            String::from("-")
+        } else {
+            goal.monthly_needed(today)
+                .map_or(String::from("overdue"), |amount| format!("{:.2}", amount))
+        };
+        let projected = match goal.projected_completion(today) {
+            Some(date) if goal.is_reached() => format!("reached {}", date),
+            Some(date) if date > goal.deadline => format!("{} (late)", date),
+            Some(date) => date.to_string(),
+            None => String::from("-"),
+        };
+
+        println!(
+            "{:<20} {:>10.2} {:>10.2} {:>7.1}%  {:<11} {:>12}  {}",
+            goal.name,
+            goal.target,
+            goal.saved(),
+            goal.progress(),
+            goal.deadline,
+            monthly,
+            projected
+        );
+    }
+
+    Ok(())
+}
+
+pub fn delete_goal_handler(cli: &mut TrackerCli, args: &[&str]) -> Result<(), BtrError> {
+    let name = args[2..].join(" ");
+    if name.is_empty() {
+        return Err(BtrError::InvalidData(Some(String::from(
+            "Usage: delete goal <name>",
+        ))));
+    }
+
+    let manager = &mut cli.tracker_manager;
+    let Some(goal) = find_goal(manager.get_goals(), &name) else {
+        return Err(BtrError::InvalidData(Some(format!(
+            "Unknown goal '{}'.",
+            name
+        ))));
+    };
+    if !confirm(&format!(
+        "Delete goal '{}' with {:.2} PLN saved?",
+        goal.name,
+        goal.saved()
+    ))? {
+        return Ok(());
+    }
+
+    let name = goal.name.clone();
+    manager.update_goals(|goals| goals.retain(|goal| goal.name != name))?;
+    println!("!> Goal has been sucesfully removed.");
+
+    Ok(())
+}
@@ -0,0 +1,131 @@
+use super::{
+    account::{account_from_args, account_from_prompt},
+    optional_input,
+    sheet::{add_to_sheet, added_to},
+};
+use crate::{
+    console::{args, cli::TrackerCli},
+    database::expense::IncomeRecord,
+    error::BtrError,
+};
+use chrono::Utc;
+
+/*
+ * One-line form: add income <source> <amount> [--date YYYY-MM-DD] [--note <text>]
+ * [--account <name>]
+ */
+pub fn add_income_handler(cli: &mut TrackerCli, args: &[&str]) -> Result<(), BtrError> {
+    let accounts = cli.tracker_manager.get_accounts();
+    let income = if args.len() > 2 {
+        income_from_args(args)?.with_account(account_from_args(accounts, args)?)
+    } else {
+        income_from_prompt()?.with_account(account_from_prompt(accounts)?)
+    };
+
+    let sheet_name = add_to_sheet(&mut cli.tracker_manager, income.logged_on(), |sheet| {
+        sheet.income_mut().push(income);
+    })?;
+
+    println!(
+        "!> Income added{}!",
+        added_to(&cli.tracker_manager, &sheet_name)
+    );
+
+    Ok(())
+}
+
+fn income_from_args(args: &[&str]) -> Result<IncomeRecord, BtrError> {
+    let usage = || {
+        BtrError::InvalidData(Some(String::from(
+            "Usage: add income <source> <amount> [--date YYYY-MM-DD] [--note <text>] [--account <name>]",
+        )))
+    };
+
+    let Some((amount, source)) = args::positional(&args[2..]).split_last() else {
+        return Err(usage());
+    };
+    if source.is_empty() {
+        return Err(usage());
+    }
+
+    let amount = args::parse_amount(amount)?;
+    if amount <= 0.0 {
+        return Err(BtrError::InvalidData(Some(String::from(
+            "The amount must be greater than 0.",
+        ))));
+    }
+
+    let date = match args::option_value(args, "--date") {
+        Some(date) => args::parse_date(date)?,
+        None => Utc::now().date_naive(),
+    };
+
+    Ok(IncomeRecord::new(source.join(" "), amount, date)
+        .with_note(args::option_text(args, "--note")))
+}
+
+fn income_from_prompt() -> Result<IncomeRecord, BtrError> {
+    println!("!> Enter source:");
+    let source = loop {
+        match optional_input()? {
+            Some(source) => break source,
+            None => println!("! Invalid input. The source can't be empty."),
+        }
+    };
+
+    println!("!> Enter amount:");
+    let amount = loop {
+        let input = TrackerCli::user_input()?;
+        match input.trim().parse::<f32>() {
+            Ok(num) if num > 0.0 => break num,
+            _ => println!("! Invalid input. The value must be greated then 0."),
+        }
+    };
+
+    println!("!> Enter note (optional):");
+    let note = optional_input()?;
+
+    Ok(IncomeRecord::new(source, amount, Utc::now().date_naive()).with_note(note))
+}
+
+pub fn delete_income_handler(cli: &mut TrackerCli, _args: &[&str]) -> Result<(), BtrError> {
+    let Some(active_sheet) = cli.tracker_manager.get_active_sheet() else {
+        return Err(BtrError::ActiveSheetNotSelected);
+    };
+
+    let income = active_sheet.income();
+    if income.is_empty() {
+        println!("!> No income to delete.");
+        return Ok(());
+    }
+
+    println!("?> Select an income to be deleted:");
+    for (idx, record) in income.iter().enumerate() {
+        println!(
+            "> {:>3}. {} {:<20} {:>10.2} {}",
+            idx,
+            record.logged_on(),
+            record.source(),
+            record.amount(),
+            record.note().unwrap_or_default()
+        );
+    }
+
+    let choise = loop {
+        let user_input = TrackerCli::user_input()?;
+
+        match user_input.trim().parse::<usize>() {
+            Ok(input) if input < income.len() => break input,
+            _ => {
+                println!("> Invalid input. Enter one of the listed numbers.")
+            }
+        }
+    };
+
+    cli.tracker_manager.update_active_sheet(|sheet| {
+        sheet.income_mut().remove(choise);
+    })?;
+    println!("!> Income has been sucesfully removed.");
+
+    Ok(())
+}
@@ -0,0 +1,304 @@
+pub mod account;
+pub mod debt;
+pub mod envelope;
+pub mod expense;
+pub mod export;
+pub mod goal;
+pub mod income;
+pub mod report;
+pub mod schedule;
+pub mod shared;
+pub mod sheet;
+
+use crate::{
+    console::{args, cli::TrackerCli},
+    database::{
+        expense::{ExpenseRecord, ExpenseSheet, IncomeRecord},
+        manager::TrackerManager,
+        periods::Period,
+        query::{self, FilterQuery, QUERY_KEYWORDS, RecordQuery},
+    },
+    error::BtrError,
+    utils,
+};
+use chrono::{NaiveDate, Utc};
+use std::{fs, path::PathBuf};
+
+fn get_sheet_list() -> Result<Vec<String>, BtrError> {
+    let entries = utils::sheets_dir().read_dir()?;
+
+    let sheet_list: Vec<String> = entries
+        .filter_map(|e| {
+            let path = e.ok()?.path();
+            if path.is_file() {
+                path.file_stem()
+                    .and_then(|f| f.to_str().map(|s| s.to_owned()))
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    Ok(sheet_list)
+}
+
+/* Records a listing, chart or report works on. */
+struct Scope {
+    label: String,
+    period: Period,
+    records: Vec<ExpenseRecord>,
+    /* Income of the period, left empty when the expenses are filtered by more than dates. */
+    income: Vec<IncomeRecord>,
+    /* A filter query narrowed the records down. */
+    filtered: bool,
+    /* All records of the active sheet, not a query over every sheet. */
+    active: bool,
+}
+
+/* Parses the query starting at the first query keyword, up to the next option. */
+fn filter_from_args(args: &[&str]) -> Result<Option<FilterQuery>, BtrError> {
+    let start = args.iter().enumerate().position(|(idx, arg)| {
+        QUERY_KEYWORDS.contains(&arg.to_ascii_lowercase().as_str())
+            && (idx == 0 || !args[idx - 1].starts_with("--"))
+    });
+
+    let Some(start) = start else {
+        return Ok(None);
+    };
+
+    let query = args[start..]
+        .iter()
+        .take_while(|arg| !arg.starts_with("--"))
+        .copied()
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    FilterQuery::parse(&query).map(Some)
+}
+
+/*
+ * Builds a cross-sheet query from '--period', '--from', '--to', '--category', '--tag',
+ * '--payee', '--min' and '--max'. Returns None when no filter was given.
+ */
+fn query_from_args(
+    manager: &TrackerManager,
+    args: &[&str],
+) -> Result<Option<RecordQuery>, BtrError> {
+    let mut query = RecordQuery::new();
+    let mut filtered = false;
+
+    let from = args::option_value(args, "--from")
+        .map(args::parse_date)
+        .transpose()?;
+    let to = args::option_value(args, "--to")
+        .map(args::parse_date)
+        .transpose()?;
+
+    if let Some(spec) = args::option_value(args, "--period") {
+        query = query.range(manager.parse_period(spec)?);
+        filtered = true;
+    } else if from.is_some() || to.is_some() {
+        let from = from.unwrap_or(NaiveDate::MIN);
+        let to = to.unwrap_or_else(|| Utc::now().date_naive());
+        query = query.range(Period::new(from, to)?);
+        filtered = true;
+    }
+
+    for category in args::option_values(args, "--category") {
+        query = query.category(category);
+        filtered = true;
+    }
+    for tag in args::option_values(args, "--tag") {
+        query = query.tag(tag);
+        filtered = true;
+    }
+    for payee in args::option_values(args, "--payee") {
+        query = query.payee(payee);
+        filtered = true;
+    }
+    if let Some(min) = args::option_value(args, "--min") {
+        query = query.min_amount(args::parse_amount(min)?);
+        filtered = true;
+    }
+    if let Some(max) = args::option_value(args, "--max") {
+        query = query.max_amount(args::parse_amount(max)?);
+        filtered = true;
+    }
+
+    Ok(filtered.then_some(query))
+}
+
+/*
+ * Records from all sheets when filter options or a dated query are given, from the active
+ * sheet otherwise. A filter query is applied on top of either. Money lent, borrowed or paid
+ * back is left out, as it is not spending and is listed by 'show debts'.
+ */
+fn report_scope(manager: &TrackerManager, args: &[&str]) -> Result<Scope, BtrError> {
+    let filter = filter_from_args(args)?;
+    let options = query_from_args(manager, args)?;
+
+    let (since, until) = filter
+        .as_ref()
+        .map_or((None, None), |f| (f.since(), f.until()));
+
+    let mut scope = if options.is_none() && since.is_none() && until.is_none() {
+        let Some(active_sheet) = manager.get_active_sheet() else {
+            return Err(BtrError::ActiveSheetNotSelected);
+        };
+
+        Scope {
+            label: active_sheet.name.clone(),
+            period: active_sheet.period.clone(),
+            records: active_sheet.expenses().to_vec(),
+            income: active_sheet.income().to_vec(),
+            filtered: false,
+            active: true,
+        }
+    } else {
+        let mut query = options.unwrap_or_default();
+        if query.get_range().is_none() && (since.is_some() || until.is_some()) {
+            query = query.range(Period::new(
+                since.unwrap_or(NaiveDate::MIN),
+                until.unwrap_or_else(|| Utc::now().date_naive()),
+            )?);
+        }
+
+        let records = manager.query(&query)?;
+        let income = if query.is_range_only() {
+            manager.query_income(&query)?
+        } else {
+            Vec::new()
+        };
+
+        /* Open ended ranges are narrowed down to the records actually found. */
+        let bounded = args::option_value(args, "--period").is_some()
+            || (args::option_value(args, "--from").is_some()
+                && args::option_value(args, "--to").is_some())
+            || (since.is_some() && until.is_some());
+        let period = match query.get_range() {
+            Some(range) if bounded => range.clone(),
+            _ => match query::records_span(&records) {
+                Some(span) => span,
+                None => Period::current_month()?,
+            },
+        };
+
+        let label = match args::option_value(args, "--period") {
+            Some(spec) => spec.to_string(),
+            None => format!("{}..{}", period.start(), period.end()),
+        };
+
+        Scope {
+            label,
+            period,
+            records,
+            income,
+            filtered: false,
+            active: false,
+        }
+    };
+    scope.records.retain(|record| !record.is_debt());
+
+    if let Some(filter) = filter {
+        if filter.narrows() {
+            scope.income.clear();
+        }
+        scope.records = filter.apply(scope.records);
+        scope.filtered = true;
+        scope.active = false;
+    }
+
+    Ok(scope)
+}
+
+/* Name safe to use as a part of a file name. */
+fn file_label(label: &str) -> String {
+    label
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+fn print_sheet_list(active_sheet: &Option<ExpenseSheet>) -> Result<(), BtrError> {
+    let sheet_list = get_sheet_list()?;
+
+    for (idx, sheet_name) in sheet_list.iter().enumerate() {
+        let is_active = active_sheet
+            .as_ref()
+            .map(|s| s.name == *sheet_name)
+            .unwrap_or(false);
+
+        if is_active {
+            println!(">  {}. {:<20} (ACTIVE)", idx, sheet_name);
+        } else {
+            println!(">  {}. {}", idx, sheet_name);
+        }
+    }
+
+    Ok(())
+}
+
+/* Asks a yes/no question until a valid answer is given. */
+fn confirm(question: &str) -> Result<bool, BtrError> {
+    loop {
+        println!("!> {} [Y/N]", question);
+        let user_input = TrackerCli::user_input()?;
+
+        match user_input.trim().to_ascii_lowercase().as_str() {
+            "y" => return Ok(true),
+            "n" => return Ok(false),
+            _ => {
+                println!("!> Unsupported input: '{}'", user_input.trim());
+            }
+        }
+    }
+}
+
+fn record_line(record: &ExpenseRecord) -> String {
+    /* A split expense shows the category of its first line and how many others follow. */
+    let category = match record.lines().len() {
+        0 | 1 => record.category().to_string(),
+        lines => format!("{} +{}", record.category(), lines - 1),
+    };
+
+    format!(
+        "{} {:<20} {:>10.2} {:<16} {}",
+        record.logged_on(),
+        category,
+        record.amount(),
+        record.payee().unwrap_or_default(),
+        record.note().unwrap_or_default()
+    )
+}
+
+fn print_records<'a>(records: impl IntoIterator<Item = (usize, &'a ExpenseRecord)>) {
+    for (idx, record) in records {
+        println!("> {:>3}. {}", idx, record_line(record));
+    }
+}
+
+/* Reads a line, an empty one means the value was skipped. */
+fn optional_input() -> Result<Option<String>, BtrError> {
+    let input = TrackerCli::user_input()?;
+    let input = input.trim();
+
+    Ok((!input.is_empty()).then(|| input.to_string()))
+}
+
+/* Path given with '--out', or a file in the reports directory. */
+fn output_path(args: &[&str], default_name: &str) -> Result<PathBuf, BtrError> {
+    match args::option_value(args, "--out") {
+        Some(path) => Ok(PathBuf::from(path)),
+        None => {
+            let reports_dir = utils::reports_dir();
+            fs::create_dir_all(&reports_dir)?;
+            Ok(reports_dir.join(default_name))
+        }
+    }
+}
@@ -0,0 +1,450 @@
+use super::{Scope, file_label, output_path, print_records, record_line, report_scope};
+use crate::{
+    console::{args, cli::TrackerCli},
+    database::{manager::TrackerManager, periods::Period, query::RecordQuery},
+    error::BtrError,
+    report::{
+        self, ExpenseSummary, anomaly,
+        chart::{self, ChartStyle},
+        compare::{CategoryDelta, Comparison},
+        forecast::Forecast,
+        stats::ExpenseStats,
+        summary::{self, IncomeSummary},
+    },
+};
+use chrono::{NaiveDate, Utc};
+use std::{collections::HashMap, fs};
+
+pub fn show_expenses_handler(cli: &mut TrackerCli, args: &[&str]) -> Result<(), BtrError> {
+    let manager = &cli.tracker_manager;
+    let scope = report_scope(manager, args)?;
+
+    let carried = carried_into(manager, &scope)?;
+    let summary = ExpenseSummary::new(scope.period, &scope.records, manager.get_categories())
+        .with_carried(&carried);
+
+    /* The statistics alone, to be piped to other tools. */
+    if args::has_flag(args, "--stats") && args::has_flag(args, "--json") {
+        let stats = ExpenseStats::new(&summary.period, &scope.records, Utc::now().date_naive());
+        let json = serde_json::to_string_pretty(&stats).map_err(|e| {
+            BtrError::InvalidData(Some(format!("Failed to serialize the data: {}", e)))
+        })?;
+        println!("{}", json);
+        return Ok(());
+    }
+
+    println!("\n{:<22} {}", "EXPENSES SUMMARY FOR", scope.label);
+    println!(
+        "{:<22} {} - {}",
+        "PERIOD",
+        summary.period.start(),
+        summary.period.end()
+    );
+    println!("{}\n", "-".repeat(60));
+
+    println!(
+        "{:<20} {:>12} {:>8}  {:>12}",
+        "Category", "Total", "Count", "Part Of Total"
+    );
+    println!("{}", "-".repeat(60));
+
+    for category in &summary.categories {
+        let refunded = if category.refunded > 0.0 {
+            format!(
+                "  (gross {:.2}, refunded {:.2})",
+                category.gross(),
+                category.refunded
+            )
+        } else {
+            String::new()
+        };
+        println!(
+            "{:<20} {:>9.2} PLN {:>8}  {:>10.1}%{}",
+            category.name,
+            category.total,
+            category.count,
+            summary.part_of_total(category),
+            refunded
+        );
+    }
+    println!("{}", "-".repeat(60));
+    if summary.refunded > 0.0 {
+        println!(
+            "{:<20} {:>9.2} PLN",
+            "GROSS",
+            summary.grand_total + summary.refunded
+        );
+        println!("{:<20} {:>9.2} PLN", "REFUNDS", -summary.refunded);
+    }
+    println!("{:<20} {:>9.2} PLN", "TOTAL", summary.grand_total);
+
+    if !summary.budgets.is_empty() {
+        print_budgets(&summary);
+    }
+
+    if !scope.income.is_empty() {
+        print_income(&IncomeSummary::new(&scope.income), summary.grand_total);
+    }
+
+    if scope.filtered {
+        println!("\n{:<22} {}", "MATCHING RECORDS", scope.records.len());
+        print_records(scope.records.iter().enumerate());
+    }
+
+    if args::has_flag(args, "--stats") {
+        print_stats(&ExpenseStats::new(
+            &summary.period,
+            &scope.records,
+            Utc::now().date_naive(),
+        ));
+    }
+
+    Ok(())
+}
+
+/* Budgets of the active sheet carry over from the chain of sheets before it. */
+fn carried_into(manager: &TrackerManager, scope: &Scope) -> Result<HashMap<String, f32>, BtrError> {
+    match manager.get_active_sheet() {
+        Some(sheet) if scope.active => {
+            let mut chain = manager.sheet_chain(sheet)?;
+            chain.pop();
+            Ok(summary::carried_budgets(&chain, manager.get_categories()))
+        }
+        _ => Ok(HashMap::new()),
+    }
+}
+
+fn print_budgets(summary: &ExpenseSummary) {
+    println!("\nBUDGETS");
+    println!("{}", "-".repeat(72));
+    println!(
+        "{:<20} {:>9} {:>9} {:>10} {:>9} {:>10}",
+        "Category", "Budget", "Carried", "Effective", "Spent", "Remaining"
+    );
+    println!("{}", "-".repeat(72));
+    for line in &summary.budgets {
+        let over = if line.remaining() < 0.0 { "  over" } else { "" };
+        println!(
+            "{:<20} {:>9.2} {:>+9.2} {:>10.2} {:>9.2} {:>10.2}{}",
+            line.name,
+            line.budget,
+            line.carried,
+            line.effective(),
+            line.spent,
+            line.remaining(),
+            over
+        );
+    }
+}
+
+fn print_income(income: &IncomeSummary, expenses: f32) {
+    println!("\nINCOME VS EXPENSES");
+    println!("{}", "-".repeat(60));
+    for (source, amount) in &income.sources {
+        println!("{:<20} {:>9.2} PLN", source, amount);
+    }
+    println!("{}", "-".repeat(60));
+    println!("{:<20} {:>9.2} PLN", "Income", income.total);
+    println!("{:<20} {:>9.2} PLN", "Expenses", expenses);
+    println!("{:<20} {:>+9.2} PLN", "Net balance", income.net(expenses));
+    if let Some(rate) = income.savings_rate(expenses) {
+        println!("{:<20} {:>9.1}%", "Savings rate", rate);
+    }
+}
+
+fn print_stats(stats: &ExpenseStats) {
+    println!("\nSTATISTICS");
+    println!("{}", "-".repeat(60));
+    println!("{:<24} {:>9.2} PLN", "Daily average", stats.daily_average);
+    println!("{:<24} {:>9.2} PLN", "Median expense", stats.median_expense);
+    if let Some(max) = &stats.max_expense {
+        println!(
+            "{:<24} {:>9.2} PLN  {}",
+            "Max expense",
+            max.amount(),
+            record_line(max)
+        );
+    }
+
+    if let Some(run_rate) = &stats.run_rate {
+        println!(
+            "{:<24} {:>9.2} PLN  ({} days elapsed, {} remaining)",
+            "Projected period total",
+            run_rate.projected_total,
+            run_rate.days_elapsed,
+            run_rate.days_remaining
+        );
+        println!(
+            "> At this pace you'll end the period at {:.2} PLN.",
+            run_rate.projected_total
+        );
+    }
+
+    println!("\n{:<20} {:>12} {:>8}", "Weekday", "Total", "Count");
+    for day in &stats.per_weekday {
+        println!(
+            "{:<20} {:>8.2} PLN {:>8}",
+            day.weekday, day.total, day.count
+        );
+    }
+
+    if !stats.top_payees.is_empty() {
+        println!("\n{:<20} {:>12} {:>8}", "Top payees", "Total", "Count");
+        for payee in &stats.top_payees {
+            println!(
+                "{:<20} {:>8.2} PLN {:>8}",
+                payee.payee, payee.total, payee.count
+            );
+        }
+    }
+
+    if !stats.biggest_expenses.is_empty() {
+        println!("\nBiggest expenses");
+        print_records(stats.biggest_expenses.iter().enumerate());
+    }
+}
+
+fn show_chart(
+    cli: &TrackerCli,
+    args: &[&str],
+    title: &str,
+    draw: fn(&ExpenseSummary, ChartStyle) -> String,
+) -> Result<(), BtrError> {
+    let scope = report_scope(&cli.tracker_manager, args)?;
+    let summary = ExpenseSummary::new(
+        scope.period,
+        &scope.records,
+        cli.tracker_manager.get_categories(),
+    );
+
+    println!("\n{} - {}", title, scope.label);
+    print!("{}", draw(&summary, ChartStyle::detect()));
+
+    Ok(())
+}
+
+pub fn show_chart_bars_handler(cli: &mut TrackerCli, args: &[&str]) -> Result<(), BtrError> {
+    show_chart(cli, args, "SPENDING PER CATEGORY", chart::bar_chart)
+}
+
+pub fn show_chart_sparkline_handler(cli: &mut TrackerCli, args: &[&str]) -> Result<(), BtrError> {
+    show_chart(cli, args, "DAILY SPENDING", chart::sparkline)
+}
+
+pub fn show_chart_heatmap_handler(cli: &mut TrackerCli, args: &[&str]) -> Result<(), BtrError> {
+    show_chart(cli, args, "SPENDING CALENDAR", chart::heatmap)
+}
+
+/*
+ * show compare [<base>] [<current>]: the current period defaults to the active sheet,
+ * the base one can be 'prev' or 'yoy' relative to it.
+ */
+pub fn show_compare_handler(cli: &mut TrackerCli, args: &[&str]) -> Result<(), BtrError> {
+    let manager = &cli.tracker_manager;
+    let specs = args::positional(&args[2..]);
+
+    let active_period = || match manager.get_active_sheet() {
+        Some(sheet) => Ok(sheet.period.clone()),
+        None => Err(BtrError::ActiveSheetNotSelected),
+    };
+
+    let current = match specs.get(1) {
+        Some(spec) => manager.parse_period(spec)?,
+        None => active_period()?,
+    };
+
+    let base = match specs.first().map(|s| s.to_ascii_lowercase()).as_deref() {
+        None | Some("prev") => current.prev()?,
+        Some("yoy") => current.year_before()?,
+        Some(spec) => manager.parse_period(spec)?,
+    };
+
+    let summarize = |period: Period| -> Result<ExpenseSummary, BtrError> {
+        let records = manager.query(&RecordQuery::new().range(period.clone()))?;
+        Ok(ExpenseSummary::new(
+            period,
+            &records,
+            manager.get_categories(),
+        ))
+    };
+
+    let comparison = Comparison::new(summarize(base)?, summarize(current)?);
+
+    let base_label = format!(
+        "{}..{}",
+        comparison.base.period.start(),
+        comparison.base.period.end()
+    );
+    let current_label = format!(
+        "{}..{}",
+        comparison.current.period.start(),
+        comparison.current.period.end()
+    );
+
+    println!("\n{:<22} {}", "BASE PERIOD", base_label);
+    println!("{:<22} {}", "COMPARED PERIOD", current_label);
+    println!("{}\n", "-".repeat(76));
+    println!(
+        "{:<20} {:>12} {:>12} {:>12} {:>14}",
+        "Category", "Base", "Compared", "Delta", "Change"
+    );
+    println!("{}", "-".repeat(76));
+
+    let print_delta = |delta: &CategoryDelta| {
+        let amount = |value: Option<f32>| value.map_or(String::from("-"), |v| format!("{:.2}", v));
+        let change = match (delta.base, delta.current) {
+            (Some(_), None) => String::from("only in base"),
+            (None, Some(_)) => String::from("new"),
+            _ => delta
+                .percent()
+                .map_or(String::from("-"), |p| format!("{:+.1}%", p)),
+        };
+
+        println!(
+            "{:<20} {:>12} {:>12} {:>+12.2} {:>14}",
+            delta.name,
+            amount(delta.base),
+            amount(delta.current),
+            delta.delta(),
+            change
+        );
+    };
+
+    for delta in &comparison.categories {
+        print_delta(delta);
+    }
+    println!("{}", "-".repeat(76));
+    print_delta(&comparison.total());
+
+    Ok(())
+}
+
+pub fn show_forecast_handler(cli: &mut TrackerCli, _args: &[&str]) -> Result<(), BtrError> {
+    let manager = &cli.tracker_manager;
+    let Some(active_sheet) = manager.get_active_sheet() else {
+        return Err(BtrError::ActiveSheetNotSelected);
+    };
+
+    let period = active_sheet.period.clone();
+    let history = match period.start().pred_opt() {
+        Some(last_day) => {
+            manager.query(&RecordQuery::new().range(Period::new(NaiveDate::MIN, last_day)?))?
+        }
+        None => Vec::new(),
+    };
+
+    let forecast = Forecast::new(
+        period,
+        active_sheet.expenses(),
+        &history,
+        manager.get_categories(),
+        Utc::now().date_naive(),
+    );
+
+    println!("\n{:<22} {}", "FORECAST FOR", active_sheet.name);
+    println!(
+        "{:<22} {} - {}, as of {}",
+        "PERIOD",
+        forecast.period.start(),
+        forecast.period.end(),
+        forecast.today
+    );
+    println!("{}\n", "-".repeat(92));
+    println!(
+        "{:<20} {:>9} {:>9} {:>9} {:>10} {:>8} {:>9}  Runs out",
+        "Category", "Spent", "Fixed", "Variable", "Projected", "+/-", "Budget"
+    );
+    println!("{}", "-".repeat(92));
+
+    for category in &forecast.categories {
+        let budget = category
+            .budget
+            .map_or(String::from("-"), |b| format!("{:.2}", b));
+        let runs_out = category
+            .runs_out
+            .map_or(String::from("-"), |d| d.to_string());
+
+        println!(
+            "{:<20} {:>9.2} {:>9.2} {:>9.2} {:>10.2} {:>8.2} {:>9}  {}",
+            category.name,
+            category.spent,
+            category.fixed,
+            category.variable,
+            category.projected(),
+            category.band,
+            budget,
+            runs_out
+        );
+    }
+    println!("{}", "-".repeat(92));
+    println!(
+        "{:<20} {:>9.2} PLN spent, {:.2} PLN projected (80% band {:.2} - {:.2} PLN)",
+        "TOTAL", forecast.spent, forecast.projected, forecast.low, forecast.high
+    );
+
+    if !forecast.upcoming.is_empty() {
+        println!("\nUPCOMING REPEATING COSTS");
+        for charge in &forecast.upcoming {
+            println!(
+                "> {} {:<24} {:<20} {:>10.2}",
+                charge.date, charge.label, charge.category, charge.amount
+            );
+        }
+    }
+
+    Ok(())
+}
+
+pub fn show_anomalies_handler(cli: &mut TrackerCli, args: &[&str]) -> Result<(), BtrError> {
+    let manager = &cli.tracker_manager;
+    let scope = report_scope(manager, args)?;
+
+    let history = match scope.period.start().pred_opt() {
+        Some(last_day) => {
+            manager.query(&RecordQuery::new().range(Period::new(NaiveDate::MIN, last_day)?))?
+        }
+        None => Vec::new(),
+    };
+
+    let anomalies = anomaly::find_anomalies(
+        &scope.period,
+        &scope.records,
+        &history,
+        manager.get_categories(),
+    );
+
+    println!("\n{:<22} {}", "ANOMALIES FOR", scope.label);
+    println!("{}", "-".repeat(60));
+    if anomalies.is_empty() {
+        println!("> Nothing unusual found.");
+    }
+
+    for anomaly in &anomalies {
+        match &anomaly.record {
+            Some(record) => println!("> {}  {}", record.logged_on(), anomaly.describe()),
+            None => println!("> {:<10}  {}", "period", anomaly.describe()),
+        }
+    }
+
+    Ok(())
+}
+
+pub fn report_html_handler(cli: &mut TrackerCli, args: &[&str]) -> Result<(), BtrError> {
+    let manager = &cli.tracker_manager;
+    let scope = report_scope(manager, args)?;
+
+    let carried = carried_into(manager, &scope)?;
+    let summary = ExpenseSummary::new(scope.period, &scope.records, manager.get_categories())
+        .with_carried(&carried);
+    let html = report::html::render(
+        &format!("Expenses report {}", scope.label),
+        &summary,
+        &scope.records,
+    );
+
+    let out_path = output_path(args, &format!("report-{}.html", file_label(&scope.label)))?;
+    fs::write(&out_path, html)?;
+    println!("!> Report saved to '{}'.", out_path.display());
+
+    Ok(())
+}
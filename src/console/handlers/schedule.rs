@@ -0,0 +1,164 @@
+use crate::{
+    console::{args, cli::TrackerCli},
+    database::{periods::Period, query::RecordQuery},
+    error::BtrError,
+    report::patterns,
+};
+use chrono::{NaiveDate, Utc};
+
+pub fn show_subscriptions_handler(cli: &mut TrackerCli, args: &[&str]) -> Result<(), BtrError> {
+    let today = Utc::now().date_naive();
+    let records = cli
+        .tracker_manager
+        .query(&RecordQuery::new().range(Period::new(NaiveDate::MIN, today)?))?;
+
+    let (mut lapsed, mut active): (Vec<_>, Vec<_>) = patterns::find_repeating(&records)
+        .into_iter()
+        .partition(|cost| cost.is_lapsed(today));
+    active.sort_by(|a, b| b.monthly_cost().total_cmp(&a.monthly_cost()));
+    lapsed.sort_by_key(|cost| std::cmp::Reverse(cost.last_charge().map(|c| c.0)));
+
+    if active.is_empty() && lapsed.is_empty() {
+        println!("> No repeating charges found in the sheets.");
+        return Ok(());
+    }
+
+    println!("\n{:<22} {}", "SUBSCRIPTIONS AS OF", today);
+    println!("{}\n", "-".repeat(92));
+    println!(
+        "{:<24} {:<18} {:>9} {:>6} {:>10} {:>11}  Next charge",
+        "Name", "Category", "Amount", "Every", "Monthly", "Annualized"
+    );
+    println!("{}", "-".repeat(92));
+
+    for cost in &active {
+        println!(
+            "{:<24} {:<18} {:>9.2} {:>5}d {:>10.2} {:>11.2}  {}",
+            cost.label,
+            cost.category,
+            cost.current_amount(),
+            cost.interval_days,
+            cost.monthly_cost(),
+            cost.annual_cost(),
+            cost.next_charge()
+                .map_or(String::from("-"), |d| d.to_string())
+        );
+
+        for (date, old, new) in cost.price_changes() {
+            println!(
+                "{:<24} {} {:.2} -> {:.2} PLN ({:+.1}%)",
+                "",
+                date,
+                old,
+                new,
+                (new - old) / old * 100.0
+            );
+        }
+    }
+
+    let monthly = active
+        .iter()
+        .fold(0.0, |sum, cost| sum + cost.monthly_cost());
+    let annual = active
+        .iter()
+        .fold(0.0, |sum, cost| sum + cost.annual_cost());
+    println!("{}", "-".repeat(92));
+    println!(
+        "{:<24} {:<18} {:>9} {:>6} {:>10.2} {:>11.2}",
+        "TOTAL", "", "", "", monthly, annual
+    );
+
+    if !lapsed.is_empty() && args::has_flag(args, "--all") {
+        println!("\nNO LONGER CHARGED");
+        for cost in &lapsed {
+            if let Some((date, amount)) = cost.last_charge() {
+                println!(
+                    "> {:<24} {:<18} {:>9.2} last charged on {}",
+                    cost.label, cost.category, amount, date
+                );
+            }
+        }
+    } else if !lapsed.is_empty() {
+        println!(
+            "\n> {} repeating charges stopped, use --all to list them.",
+            lapsed.len()
+        );
+    }
+
+    Ok(())
+}
+
+pub fn show_installments_handler(cli: &mut TrackerCli, args: &[&str]) -> Result<(), BtrError> {
+    let today = Utc::now().date_naive();
+    let show_all = args::has_flag(args, "--all");
+
+    let (outstanding, paid_off): (Vec<_>, Vec<_>) = cli
+        .tracker_manager
+        .get_installments()
+        .iter()
+        .partition(|plan| plan.is_outstanding(today));
+
+    if outstanding.is_empty() && (paid_off.is_empty() || !show_all) {
+        println!("> No outstanding installments.");
+        if !paid_off.is_empty() {
+            println!(
+                "> {} plans paid off, use --all to list them.",
+                paid_off.len()
+            );
+        }
+        return Ok(());
+    }
+
+    println!("\n{:<22} {}", "INSTALLMENTS AS OF", today);
+    println!("{}\n", "-".repeat(96));
+    println!(
+        "{:<20} {:<16} {:>10} {:>9} {:>7} {:>10}  {:<12} Ends",
+        "Name", "Category", "Total", "Payment", "Paid", "Remaining", "Next payment"
+    );
+    println!("{}", "-".repeat(96));
+
+    let listed = if show_all {
+        outstanding
+            .iter()
+            .chain(paid_off.iter())
+            .collect::<Vec<_>>()
+    } else {
+        outstanding.iter().collect()
+    };
+    for plan in listed {
+        println!(
+            "{:<20} {:<16} {:>10.2} {:>9.2} {:>7} {:>10.2}  {:<12} {}",
+            plan.name,
+            plan.category,
+            plan.total,
+            plan.payment(),
+            format!("{}/{}", plan.paid_count(today), plan.payments),
+            plan.remaining(today),
+            plan.next_payment(today)
+                .map_or(String::from("-"), |(date, _)| date.to_string()),
+            plan.last_payment()
+                .map_or(String::from("-"), |date| date.to_string())
+        );
+    }
+
+    let remaining = outstanding
+        .iter()
+        .fold(0.0, |sum, plan| sum + plan.remaining(today));
+    let monthly = outstanding
+        .iter()
+        .fold(0.0, |sum, plan| sum + plan.payment());
+    println!("{}", "-".repeat(96));
+    println!(
+        "{:<20} {:<16} {:>10} {:>9.2} {:>7} {:>10.2}",
+        "TOTAL", "", "", monthly, "", remaining
+    );
+
+    if !show_all && !paid_off.is_empty() {
+        println!(
+            "\n> {} plans paid off, use --all to list them.",
+            paid_off.len()
+        );
+    }
+
+    Ok(())
+}
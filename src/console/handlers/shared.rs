@@ -0,0 +1,201 @@
+use super::{
+    confirm, optional_input,
+    sheet::{add_to_sheet, added_to},
+};
+use crate::{
+    console::{args, cli::TrackerCli},
+    database::{
+        config::{Person, person::find_person},
+        expense::{Settlement, SharedExpense, Split},
+        query::RecordQuery,
+    },
+    error::BtrError,
+    report::balances,
+};
+use chrono::Utc;
+use std::collections::BTreeMap;
+
+fn known_person(people: &[Person], name: &str) -> Result<String, BtrError> {
+    find_person(people, name)
+        .map(|person| person.name.clone())
+        .ok_or_else(|| BtrError::InvalidData(Some(format!("Unknown person '{}'.", name))))
+}
+
+/*
+ * Parses 'equal', 'equal:<name>,<name>', 'percent:<name>=<pct>,...' or
+ * 'exact:<name>=<amount>,...'. A plain 'equal' splits among everyone configured.
+ */
+fn parse_split(people: &[Person], spec: &str) -> Result<Split, BtrError> {
+    let (kind, list) = spec.split_once(':').unwrap_or((spec, ""));
+    let names = || -> Result<Vec<String>, BtrError> {
+        list.split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(|name| known_person(people, name))
+            .collect()
+    };
+    let shares = || -> Result<BTreeMap<String, f32>, BtrError> {
+        list.split(',')
+            .map(str::trim)
+            .filter(|share| !share.is_empty())
+            .map(|share| {
+                let Some((name, value)) = share.split_once('=') else {
+                    return Err(BtrError::InvalidData(Some(format!(
+                        "'{}' is not a share. Expected <name>=<value>.",
+                        share
+                    ))));
+                };
+                Ok((
+                    known_person(people, name.trim())?,
+                    args::parse_amount(value.trim())?,
+                ))
+            })
+            .collect()
+    };
+
+    match kind.to_ascii_lowercase().as_str() {
+        "equal" if list.is_empty() => Ok(Split::Equal {
+            people: people.iter().map(|p| p.name.clone()).collect(),
+        }),
+        "equal" => Ok(Split::Equal { people: names()? }),
+        "percent" => Ok(Split::Percent { shares: shares()? }),
+        "exact" => Ok(Split::Exact { shares: shares()? }),
+        _ => Err(BtrError::InvalidData(Some(format!(
+            "Unknown split '{}'. Use equal, percent or exact.",
+            kind
+        )))),
+    }
+}
+
+/* Payer given with '--paid-by' and the split with '--split', equal among everyone by default. */
+pub(super) fn shared_from_args(
+    people: &[Person],
+    args: &[&str],
+) -> Result<Option<SharedExpense>, BtrError> {
+    let split = args::option_value(args, "--split");
+    let Some(payer) = args::option_text(args, "--paid-by") else {
+        return match split {
+            Some(_) => Err(BtrError::InvalidData(Some(String::from(
+                "A split needs a payer, add --paid-by <person>.",
+            )))),
+            None => Ok(None),
+        };
+    };
+
+    Ok(Some(SharedExpense {
+        payer: known_person(people, &payer)?,
+        split: parse_split(people, split.unwrap_or("equal"))?,
+    }))
+}
+
+/* Asks for a payer when people are configured, an empty answer keeps the expense personal. */
+pub(super) fn shared_from_prompt(
+    people: &[Person],
+    amount: f32,
+) -> Result<Option<SharedExpense>, BtrError> {
+    if people.is_empty() {
+        return Ok(None);
+    }
+
+    println!("!> Enter who paid for a shared expense (optional):");
+    let payer = loop {
+        let Some(input) = optional_input()? else {
+            return Ok(None);
+        };
+        match known_person(people, &input) {
+            Ok(payer) => break payer,
+            Err(e) => println!("! {}", e),
+        }
+    };
+
+    println!(
+        "!> Enter the split: equal[:<names>], percent:<name>=<pct>,... or exact:<name>=<amount>,... (empty for equal):"
+    );
+    loop {
+        let spec = optional_input()?.unwrap_or_else(|| String::from("equal"));
+        let shared = parse_split(people, &spec).map(|split| SharedExpense {
+            payer: payer.clone(),
+            split,
+        });
+
+        match shared.and_then(|shared| shared.validate(amount).map(|_| shared)) {
+            Ok(shared) => return Ok(Some(shared)),
+            Err(e) => println!("! {}", e),
+        }
+    }
+}
+
+/* Who owes whom across all sheets, with the transfers that would settle it. */
+pub fn show_balances_handler(cli: &mut TrackerCli, _args: &[&str]) -> Result<(), BtrError> {
+    let manager = &cli.tracker_manager;
+    let everything = RecordQuery::new();
+    let balances = balances::balances(
+        &manager.query(&everything)?,
+        &manager.query_settlements(&everything)?,
+    );
+
+    println!("\nSHARED EXPENSES BALANCES");
+    println!("{}", "-".repeat(60));
+    for (person, balance) in &balances {
+        let state = if balance.abs() < 0.005 {
+            "settled"
+        } else if *balance > 0.0 {
+            "is owed"
+        } else {
+            "owes"
+        };
+        println!("{:<20} {:<8} {:>10.2} PLN", person, state, balance.abs());
+    }
+    println!("{}", "-".repeat(60));
+
+    print_settle_transfers(&balances::settle(&balances));
+
+    Ok(())
+}
+
+fn print_settle_transfers(transfers: &[(String, String, f32)]) {
+    if transfers.is_empty() {
+        println!("> Everyone is settled up.");
+        return;
+    }
+
+    for (from, to, amount) in transfers {
+        println!("> {} pays {} {:.2} PLN", from, to, amount);
+    }
+}
+
+/*
+ * Records the transfers that bring every shared balance to zero. They go to the sheet
+ * 'record_sheet' chooses for today.
+ */
+pub fn settle_handler(cli: &mut TrackerCli, _args: &[&str]) -> Result<(), BtrError> {
+    let manager = &mut cli.tracker_manager;
+
+    let everything = RecordQuery::new();
+    let balances = balances::balances(
+        &manager.query(&everything)?,
+        &manager.query_settlements(&everything)?,
+    );
+    let transfers = balances::settle(&balances);
+
+    println!("?> Proposed transfers:");
+    print_settle_transfers(&transfers);
+    if transfers.is_empty() || !confirm("Record these transfers as settled?")? {
+        return Ok(());
+    }
+
+    let today = Utc::now().date_naive();
+    let sheet_name = add_to_sheet(manager, today, |sheet| {
+        sheet
+            .settlements_mut()
+            .extend(transfers.into_iter().map(|(from, to, amount)| Settlement {
+                from,
+                to,
+                amount,
+                logged_on: today,
+            }));
+    })?;
+    println!("!> Settlements recorded{}.", added_to(manager, &sheet_name));
+
+    Ok(())
+}
@@ -0,0 +1,379 @@
+use super::{confirm, get_sheet_list, print_sheet_list, record_line};
+use crate::{
+    console::{args, cli::TrackerCli},
+    database::{
+        config::{Routing, rollover::RolloverMode},
+        expense::ExpenseSheet,
+        manager::TrackerManager,
+        periods::Period,
+    },
+    error::{BtrError, BtrErrorKind},
+    utils,
+};
+use chrono::{Datelike, NaiveDate, Utc};
+use std::{fs, io::ErrorKind};
+
+fn create_sheet_with_prompt(
+    manager: &mut TrackerManager,
+    sheet_name: &str,
+    period: Period,
+) -> Result<(), BtrError> {
+    /* Period is a small data type - simple clone use is enough. */
+    if let Err(e) = manager.new_sheet(sheet_name, period.clone(), false) {
+        if e.kind() == BtrErrorKind::Io(ErrorKind::AlreadyExists) {
+            if !confirm(&format!(
+                "Sheet '{}.json' already exists. Overwrite?",
+                sheet_name
+            ))? {
+                return Ok(());
+            }
+            manager.new_sheet(sheet_name, period, true)?;
+        } else {
+            return Err(e);
+        }
+    } else {
+        println!("> Sheet '{}.json' created succesfully.", sheet_name);
+    }
+
+    add_due_charges(manager, sheet_name)
+}
+
+/* Adds the recurring expenses and installments due in a sheet and lists what was added. */
+pub fn add_due_charges(manager: &mut TrackerManager, sheet_name: &str) -> Result<(), BtrError> {
+    let added = manager.apply_scheduled(sheet_name, Utc::now().date_naive())?;
+
+    if !added.is_empty() {
+        println!("> Scheduled charges added to '{}':", sheet_name);
+        for record in &added {
+            println!(">      {}", record_line(record));
+        }
+    }
+
+    Ok(())
+}
+
+/*
+ * Moves on to the sheet of today once the period of the active sheet is over. A sheet of
+ * the following period is selected when it exists, otherwise created, asking first unless
+ * the rollover is automatic.
+ */
+pub fn roll_over(cli: &mut TrackerCli) -> Result<(), BtrError> {
+    let manager = &mut cli.tracker_manager;
+    let rollover = manager.get_rollover().clone();
+    let today = Utc::now().date_naive();
+
+    let Some(active) = manager.get_active_sheet() else {
+        return Ok(());
+    };
+    if rollover.mode == RolloverMode::Off
+        || *active.period.end() >= today
+        || cli.kept_sheet.as_ref() == Some(&active.name)
+    {
+        return Ok(());
+    }
+    let active_name = active.name.clone();
+
+    /* Periods with no sheet at all are skipped, the new sheet is the one holding today. */
+    let mut period = active.period.next()?;
+    while !period.contains(today) {
+        period = period.next()?;
+    }
+    let sheet_name = rollover
+        .sheet_name(&period)
+        .unwrap_or_else(|| sheet_label(&period));
+
+    let existing = manager
+        .load_sheets()?
+        .into_iter()
+        .find(|sheet| sheet.period == period || sheet.name == sheet_name);
+    let question = match &existing {
+        Some(sheet) => format!(
+            "Period of '{}' is over. Switch to '{}'?",
+            active_name, sheet.name
+        ),
+        None => format!(
+            "Period of '{}' is over. Create and select '{}' for {}?",
+            active_name, sheet_name, period
+        ),
+    };
+    if rollover.mode == RolloverMode::Ask && !confirm(&question)? {
+        cli.kept_sheet = Some(active_name);
+        return Ok(());
+    }
+
+    let sheet_name = match existing {
+        Some(sheet) => sheet.name,
+        None => {
+            manager.new_sheet(&sheet_name, period, false)?;
+            println!("> Sheet '{}.json' created succesfully.", sheet_name);
+            sheet_name
+        }
+    };
+    manager.set_active_sheet(Some(&sheet_name))?;
+    println!("> Active sheet is now '{}'.", sheet_name);
+
+    add_due_charges(manager, &sheet_name)
+}
+
+/*
+ * Sheet a new record of the date goes to. With routing by date it is the sheet whose period
+ * holds the date: the active one when it does, the one the user picks when several sheets
+ * overlap on it, a new one when there is none. Otherwise it is the active sheet.
+ */
+pub(super) fn record_sheet(
+    manager: &mut TrackerManager,
+    date: NaiveDate,
+) -> Result<String, BtrError> {
+    let active = manager.get_active_sheet().as_ref();
+    if manager.get_routing() == Routing::Active {
+        return active
+            .map(|sheet| sheet.name.clone())
+            .ok_or(BtrError::ActiveSheetNotSelected);
+    }
+    if let Some(active) = active
+        && active.period.contains(date)
+    {
+        return Ok(active.name.clone());
+    }
+
+    let mut matching: Vec<ExpenseSheet> = manager
+        .load_sheets()?
+        .into_iter()
+        .filter(|sheet| sheet.period.contains(date))
+        .collect();
+    matching.sort_by_key(|sheet| *sheet.period.start());
+
+    match matching.len() {
+        0 => create_sheet_for(manager, date),
+        1 => Ok(matching.remove(0).name),
+        _ => {
+            println!(
+                "?> Sheets overlapping on {}, select the one for the record:",
+                date
+            );
+            for (idx, sheet) in matching.iter().enumerate() {
+                println!(">  {}. {:<20} {}", idx, sheet.name, sheet.period);
+            }
+
+            let choice = loop {
+                let user_input = TrackerCli::user_input()?;
+
+                match user_input.trim().parse::<usize>() {
+                    Ok(idx) if idx < matching.len() => break idx,
+                    _ => {
+                        println!(
+                            "!> Invalid input. Enter a number in range from 0 to {}.",
+                            matching.len() - 1
+                        );
+                    }
+                }
+            };
+            Ok(matching.swap_remove(choice).name)
+        }
+    }
+}
+
+/*
+ * A sheet for a date no sheet holds. Its period continues the periods of the active sheet,
+ * a month when no sheet is active, and must not overlap any other sheet.
+ */
+fn create_sheet_for(manager: &mut TrackerManager, date: NaiveDate) -> Result<String, BtrError> {
+    let mut period = match manager.get_active_sheet() {
+        Some(active) => active.period.clone(),
+        None => Period::month(date.month(), date.year())?,
+    };
+    while *period.end() < date {
+        period = period.next()?;
+    }
+    while *period.start() > date {
+        period = period.prev()?;
+    }
+    if !period.contains(date) {
+        period = Period::month(date.month(), date.year())?;
+    }
+
+    let sheet_name = manager
+        .get_rollover()
+        .sheet_name(&period)
+        .unwrap_or_else(|| sheet_label(&period));
+    let sheets = manager.load_sheets()?;
+    if let Some(sheet) = sheets
+        .iter()
+        .find(|sheet| sheet.name == sheet_name || sheet.period.overlaps(&period))
+    {
+        return Err(BtrError::InvalidData(Some(format!(
+            "No sheet holds {}, and a sheet '{}' for {} would clash with sheet '{}' ({}). Add a sheet for the date with 'add sheet'.",
+            date, sheet_name, period, sheet.name, sheet.period
+        ))));
+    }
+
+    if !confirm(&format!(
+        "No sheet holds {}. Create '{}' for {}?",
+        date, sheet_name, period
+    ))? {
+        return Err(BtrError::InvalidData(Some(format!(
+            "No sheet holds {}, the record was not added.",
+            date
+        ))));
+    }
+
+    manager.new_sheet(&sheet_name, period, false)?;
+    println!("> Sheet '{}.json' created succesfully.", sheet_name);
+    add_due_charges(manager, &sheet_name)?;
+
+    Ok(sheet_name)
+}
+
+/* Adds a record to the sheet 'record_sheet' chooses for its date, returns the sheet name. */
+pub(super) fn add_to_sheet<F>(
+    manager: &mut TrackerManager,
+    date: NaiveDate,
+    adder: F,
+) -> Result<String, BtrError>
+where
+    F: FnOnce(&mut ExpenseSheet),
+{
+    let sheet_name = record_sheet(manager, date)?;
+    let mut sheet = TrackerManager::load_sheet(&sheet_name)?;
+    adder(&mut sheet);
+    manager.save_sheet(sheet)?;
+
+    Ok(sheet_name)
+}
+
+/* Names the sheet a record went to when it is not the active one. */
+pub(super) fn added_to(manager: &TrackerManager, sheet_name: &str) -> String {
+    match manager.get_active_sheet() {
+        Some(active) if active.name == sheet_name => String::new(),
+        _ => format!(" to '{}'", sheet_name),
+    }
+}
+
+/*
+ * add sheet [<period spec>] | [<name>] [--period <period spec>]
+ * A single period spec written as 'is_period_spec' expects names the sheet after the period.
+ * Other names are custom ones, for the current month unless '--period' is given.
+ */
+pub fn add_sheet_handler(cli: &mut TrackerCli, args: &[&str]) -> Result<(), BtrError> {
+    let manager = &mut cli.tracker_manager;
+    let name = args::positional(&args[2..]).join(" ");
+
+    /* Determine a period */
+    let (period, spec_named) = match args::option_value(args, "--period") {
+        Some(spec) => (manager.parse_period(spec)?, false),
+        None if args::has_flag(args, "--next") => match manager.get_active_sheet() {
+            Some(active) => (active.period.next()?, false),
+            None => return Err(BtrError::ActiveSheetNotSelected),
+        },
+        None if is_period_spec(&name) => (manager.parse_period(&name)?, true),
+        None => (Period::current_month()?, false),
+    };
+
+    /* Determine a sheet name */
+    let sheet_name = if !name.is_empty() && !spec_named {
+        /* Special case: custom sheet name */
+        name
+    } else {
+        /* Default case. */
+        sheet_label(&period)
+    };
+
+    create_sheet_with_prompt(manager, &sheet_name, period)
+}
+
+/*
+ * Whether a sheet name is written as one of the period specs 'add sheet' takes: YYYY-MM,
+ * YYYY-Qn, YYYY-Wnn, FYyyyy, a range of days or payday:N. Any other name is a custom one.
+ */
+fn is_period_spec(name: &str) -> bool {
+    let digits = |text: &str, len: std::ops::RangeInclusive<usize>| {
+        len.contains(&text.len()) && text.chars().all(|c| c.is_ascii_digit())
+    };
+
+    if name.contains("..") || name.starts_with("payday:") {
+        return true;
+    }
+    if let Some(year) = name.strip_prefix("FY").or_else(|| name.strip_prefix("fy")) {
+        return digits(year, 4..=4);
+    }
+
+    match name.split_once('-') {
+        Some((year, rest)) if digits(year, 4..=4) => match rest.split_at_checked(1) {
+            Some(("Q" | "q", quarter)) => digits(quarter, 1..=1),
+            Some(("W" | "w", week)) => digits(week, 1..=2),
+            _ => digits(rest, 2..=2),
+        },
+        _ => false,
+    }
+}
+
+/* Months keep the 'MM-YYYY' sheet names, other periods are named after their spec. */
+fn sheet_label(period: &Period) -> String {
+    let start = period.start();
+    match Period::month(start.month(), start.year()) {
+        Ok(month) if month.end() == period.end() && start.day() == 1 => {
+            format!("{:02}-{}", start.month(), start.year())
+        }
+        _ => period.to_string(),
+    }
+}
+
+pub fn show_sheets_handler(cli: &mut TrackerCli, _args: &[&str]) -> Result<(), BtrError> {
+    let active_sheet = cli.tracker_manager.get_active_sheet();
+
+    println!("? SHEETS:");
+    print_sheet_list(active_sheet)?;
+
+    Ok(())
+}
+
+pub fn select_handler(cli: &mut TrackerCli, args: &[&str]) -> Result<(), BtrError> {
+    match args.len() {
+        2 => {
+            cli.tracker_manager.set_active_sheet(Some(args[1]))?;
+            add_due_charges(&mut cli.tracker_manager, args[1])?;
+        }
+        _ => {
+            eprintln!("! Wrong input. Sheet name must be provided.")
+        }
+    }
+
+    Ok(())
+}
+
+pub fn delete_sheet_handler(cli: &mut TrackerCli, _args: &[&str]) -> Result<(), BtrError> {
+    let active_sheet = cli.tracker_manager.get_active_sheet();
+
+    println!("?> Select a sheet to be deleted:");
+    print_sheet_list(active_sheet)?;
+
+    let sheet_list = get_sheet_list()?;
+    let choise = loop {
+        let user_input = TrackerCli::user_input()?;
+
+        match user_input.trim().parse::<usize>() {
+            Ok(idx) if idx < sheet_list.len() => break idx,
+            _ => {
+                println!(
+                    "!> Invalid input. Enter a number in range from 0 to {}.",
+                    sheet_list.len()
+                )
+            }
+        }
+    };
+
+    /* TO DO: Handle a case when the sheet was active. */
+    if let Some(sheet) = active_sheet
+        && sheet.name == sheet_list[choise]
+    {
+        cli.tracker_manager.set_active_sheet(None)?
+    }
+
+    fs::remove_file(utils::sheets_dir().join(format!("{}.json", sheet_list[choise])))?;
+    println!(
+        "!> Sheet {} has been sucessfully removed.",
+        sheet_list[choise]
+    );
+
+    Ok(())
+}
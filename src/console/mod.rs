@@ -1,3 +1,4 @@
+mod args;
 pub mod cli;
 mod cmd;
 mod handlers;
@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+
+/* A place money is kept in, e.g. a bank account, a card or cash. */
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Account {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /* Balance before the first record of the tracker. */
+    #[serde(default)]
+    pub opening_balance: f32,
+    /* Records without an account are attributed to the default one. */
+    #[serde(default)]
+    pub default: bool,
+}
+
+/* Case insensitive lookup of an account by its name. */
+pub fn find_account<'a>(accounts: &'a [Account], name: &str) -> Option<&'a Account> {
+    accounts.iter().find(|a| a.name.eq_ignore_ascii_case(name))
+}
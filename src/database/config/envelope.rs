@@ -0,0 +1,30 @@
+use crate::database::config::recurring::toml_date_opt;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+/* What happens with the money left in an envelope when a sheet ends. */
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Leftover {
+    /* Stays in the envelope of the next sheet. */
+    #[default]
+    Rollover,
+    /* Goes back to the money to be assigned in the next sheet. */
+    Sweep,
+}
+
+/*
+ * Envelope budgeting, on when configured, e.g.:
+ *   [envelopes]
+ *   leftover = "sweep"
+ *   since = 2025-01-01
+ * Income of a sheet is assigned to category envelopes and expenses draw them down. Sheets
+ * ending before 'since' are left out.
+ */
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct EnvelopeMode {
+    #[serde(default)]
+    pub leftover: Leftover,
+    #[serde(default, deserialize_with = "toml_date_opt")]
+    pub since: Option<NaiveDate>,
+}
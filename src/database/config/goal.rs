@@ -0,0 +1,93 @@
+use chrono::{Datelike, Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+/* Average length of a month in days, used to project contributions. */
+const DAYS_PER_MONTH: f32 = 30.44;
+
+/* Money put towards a goal. */
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Contribution {
+    pub amount: f32,
+    pub logged_on: NaiveDate,
+}
+
+/* An amount to save by a date, e.g. 'Vacation 6000 PLN by 2026-07'. */
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Goal {
+    pub name: String,
+    pub target: f32,
+    pub deadline: NaiveDate,
+    pub created: NaiveDate,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub contributions: Vec<Contribution>,
+}
+
+impl Goal {
+    pub fn new(name: String, target: f32, deadline: NaiveDate, created: NaiveDate) -> Self {
+        Self {
+            name,
+            target,
+            deadline,
+            created,
+            contributions: Vec::new(),
+        }
+    }
+
+    pub fn saved(&self) -> f32 {
+        self.contributions.iter().fold(0.0, |sum, c| sum + c.amount)
+    }
+
+    pub fn remaining(&self) -> f32 {
+        (self.target - self.saved()).max(0.0)
+    }
+
+    /* Saved part of the target in percent. */
+    pub fn progress(&self) -> f32 {
+        if self.target > 0.0 {
+            self.saved() / self.target * 100.0
+        } else {
+            100.0
+        }
+    }
+
+    pub fn is_reached(&self) -> bool {
+        self.remaining() < 0.005
+    }
+
+    /* Needed every month until the deadline, the current one included. None once it passed. */
+    pub fn monthly_needed(&self, today: NaiveDate) -> Option<f32> {
+        let months = (self.deadline.year() - today.year()) * 12 + self.deadline.month() as i32
+            - today.month() as i32
+            + 1;
+        (self.deadline >= today && months > 0).then(|| self.remaining() / months as f32)
+    }
+
+    /*
+     * Day the target is reached at the average monthly pace of the contributions so far,
+     * counted from the creation of the goal or the first contribution made before it.
+     * None without any contributions.
+     */
+    pub fn projected_completion(&self, today: NaiveDate) -> Option<NaiveDate> {
+        let dates = self.contributions.iter().map(|c| c.logged_on);
+        if self.is_reached() {
+            return dates.max();
+        }
+
+        let since = dates
+            .min()
+            .map_or(self.created, |first| first.min(self.created));
+        let months = ((today - since).num_days() as f32 / DAYS_PER_MONTH).max(1.0);
+        let pace = self.saved() / months;
+        if pace <= 0.0 {
+            return None;
+        }
+
+        let days = (self.remaining() / pace * DAYS_PER_MONTH).ceil() as i64;
+        today.checked_add_signed(Duration::days(days))
+    }
+}
+
+/* Case insensitive lookup of a goal by its name. */
+pub fn find_goal<'a>(goals: &'a [Goal], name: &str) -> Option<&'a Goal> {
+    goals.iter().find(|g| g.name.eq_ignore_ascii_case(name))
+}
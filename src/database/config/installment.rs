@@ -0,0 +1,99 @@
+use crate::database::{config::recurring::toml_date, expense::ExpenseRecord, periods::Period};
+use chrono::{Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+/*
+ * A purchase paid off in monthly payments, e.g.:
+ *   [[installments]]
+ *   name = "Laptop"
+ *   category = "Electronics"
+ *   total = 4999.0
+ *   payments = 10
+ *   start = 2025-03-15
+ * Payments fall on the day of 'start' in consecutive months, the last one evens out the
+ * rounding of the others.
+ */
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Installment {
+    pub name: String,
+    pub category: String,
+    pub total: f32,
+    pub payments: u32,
+    #[serde(deserialize_with = "toml_date")]
+    pub start: NaiveDate,
+    #[serde(default)]
+    pub payee: Option<String>,
+}
+
+impl Installment {
+    /* Key under which applied payments are remembered in a sheet. */
+    pub fn source(&self) -> String {
+        format!("installment:{}", self.name)
+    }
+
+    /* A regular payment, rounded to cents. */
+    pub fn payment(&self) -> f32 {
+        (self.total / self.payments.max(1) as f32 * 100.0).round() / 100.0
+    }
+
+    /* Date and amount of every payment of the plan. */
+    pub fn schedule(&self) -> Vec<(NaiveDate, f32)> {
+        let payments = self.payments.max(1);
+        let last = self.total - self.payment() * (payments - 1) as f32;
+
+        (0..payments)
+            .filter_map(|n| {
+                let date = self.start.checked_add_months(Months::new(n))?;
+                let amount = if n + 1 == payments {
+                    last
+                } else {
+                    self.payment()
+                };
+                Some((date, amount))
+            })
+            .collect()
+    }
+
+    /* Payments falling within the period. */
+    pub fn charges(&self, period: &Period) -> Vec<(NaiveDate, f32)> {
+        self.schedule()
+            .into_iter()
+            .filter(|(date, _)| period.contains(*date))
+            .collect()
+    }
+
+    /* Number of payments due by 'today'. */
+    pub fn paid_count(&self, today: NaiveDate) -> usize {
+        self.schedule()
+            .iter()
+            .filter(|(date, _)| *date <= today)
+            .count()
+    }
+
+    pub fn remaining(&self, today: NaiveDate) -> f32 {
+        self.schedule()
+            .iter()
+            .filter(|(date, _)| *date > today)
+            .fold(0.0, |sum, (_, amount)| sum + amount)
+    }
+
+    pub fn next_payment(&self, today: NaiveDate) -> Option<(NaiveDate, f32)> {
+        self.schedule().into_iter().find(|(date, _)| *date > today)
+    }
+
+    pub fn last_payment(&self) -> Option<NaiveDate> {
+        self.schedule().last().map(|(date, _)| *date)
+    }
+
+    pub fn is_outstanding(&self, today: NaiveDate) -> bool {
+        self.next_payment(today).is_some()
+    }
+
+    /* The payment as an expense, noted with its number, e.g. 'Laptop 3/10'. */
+    pub fn to_record(&self, date: NaiveDate, amount: f32) -> ExpenseRecord {
+        let number = self.paid_count(date);
+        ExpenseRecord::new(self.category.clone(), amount, date)
+            .with_payee(self.payee.clone())
+            .with_note(Some(format!("{} {}/{}", self.name, number, self.payments)))
+    }
+}
@@ -1,5 +1,21 @@
+pub mod account;
+pub mod envelope;
+pub mod goal;
+pub mod installment;
+pub mod person;
+pub mod recurring;
+pub mod rollover;
+pub mod routing;
 pub mod state;
 pub mod tracker;
 
+pub use account::Account;
+pub use envelope::EnvelopeMode;
+pub use goal::Goal;
+pub use installment::Installment;
+pub use person::Person;
+pub use recurring::RecurringExpense;
+pub use rollover::Rollover;
+pub use routing::Routing;
 pub use state::TrackerState;
 pub use tracker::TrackerConfig;
@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+
+/* Someone expenses are shared with. */
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Person {
+    pub name: String,
+}
+
+/* Case insensitive lookup of a person by their name. */
+pub fn find_person<'a>(people: &'a [Person], name: &str) -> Option<&'a Person> {
+    people.iter().find(|p| p.name.eq_ignore_ascii_case(name))
+}
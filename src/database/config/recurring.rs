@@ -0,0 +1,134 @@
+use crate::database::{expense::ExpenseRecord, periods::Period};
+use chrono::{Datelike, Duration, Months, NaiveDate};
+use serde::{Deserialize, Deserializer, Serialize, de::Error};
+
+/*
+ * How often a recurring expense is charged, e.g.:
+ *   schedule = { every = "month", day = 10 }
+ *   schedule = { every = "months", count = 3, day = 1 }
+ *   schedule = { every = "week" }
+ *   schedule = { every = "year" }
+ * Weekly and yearly charges fall on the weekday and date of 'start'.
+ */
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "every", rename_all = "snake_case")]
+pub enum Schedule {
+    Month { day: u32 },
+    Months { count: u32, day: u32 },
+    Week,
+    Year,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RecurringExpense {
+    pub name: String,
+    pub category: String,
+    pub amount: f32,
+    pub schedule: Schedule,
+    #[serde(deserialize_with = "toml_date")]
+    pub start: NaiveDate,
+    #[serde(default, deserialize_with = "toml_date_opt")]
+    pub end: Option<NaiveDate>,
+    #[serde(default)]
+    pub payee: Option<String>,
+}
+
+impl RecurringExpense {
+    /* Key under which applied occurrences are remembered in a sheet. */
+    pub fn source(&self) -> String {
+        format!("recurring:{}", self.name)
+    }
+
+    /* Charge dates within the period, limited by the start and end of the definition. */
+    pub fn occurrences(&self, period: &Period) -> Vec<NaiveDate> {
+        let first = self.start.max(*period.start());
+        let last = match self.end {
+            Some(end) => end.min(*period.end()),
+            None => *period.end(),
+        };
+        if first > last {
+            return Vec::new();
+        }
+
+        let in_range = |date: &NaiveDate| first <= *date && *date <= last;
+
+        match &self.schedule {
+            Schedule::Week => {
+                let offset = (first - self.start).num_days().rem_euclid(7);
+                let mut date = first + Duration::days((7 - offset) % 7);
+                let mut dates = Vec::new();
+                while date <= last {
+                    dates.push(date);
+                    date += Duration::days(7);
+                }
+                dates
+            }
+            Schedule::Year => (first.year()..=last.year())
+                .filter_map(|year| clamped_date(year, self.start.month(), self.start.day()))
+                .filter(in_range)
+                .collect(),
+            Schedule::Month { day } => month_dates(first, last, self.start, 1, *day),
+            Schedule::Months { count, day } => {
+                month_dates(first, last, self.start, (*count).max(1), *day)
+            }
+        }
+    }
+
+    pub fn to_record(&self, date: NaiveDate) -> ExpenseRecord {
+        ExpenseRecord::new(self.category.clone(), self.amount, date)
+            .with_payee(self.payee.clone())
+            .with_note(Some(self.name.clone()))
+    }
+}
+
+/* Day 'day' of every 'step'th month counted from the month of 'anchor'. */
+fn month_dates(
+    first: NaiveDate,
+    last: NaiveDate,
+    anchor: NaiveDate,
+    step: u32,
+    day: u32,
+) -> Vec<NaiveDate> {
+    let mut dates = Vec::new();
+    let mut cursor = NaiveDate::from_ymd_opt(anchor.year(), anchor.month(), 1);
+
+    while let Some(month) = cursor {
+        if month > last {
+            break;
+        }
+
+        if let Some(date) = clamped_date(month.year(), month.month(), day)
+            && first <= date
+            && date <= last
+        {
+            dates.push(date);
+        }
+
+        cursor = month.checked_add_months(Months::new(step));
+    }
+
+    dates
+}
+
+/* The given day of the month, moved to the last day for shorter months. */
+fn clamped_date(year: i32, month: u32, day: u32) -> Option<NaiveDate> {
+    let last_day = Period::month(month, year).ok()?.end().day();
+    NaiveDate::from_ymd_opt(year, month, day.clamp(1, last_day))
+}
+
+/* Accepts both a bare TOML date (start = 2025-01-01) and a quoted one. */
+pub fn toml_date<'de, D: Deserializer<'de>>(deserializer: D) -> Result<NaiveDate, D::Error> {
+    let text = match toml::Value::deserialize(deserializer)? {
+        toml::Value::Datetime(datetime) => datetime.to_string(),
+        toml::Value::String(text) => text,
+        other => return Err(D::Error::custom(format!("expected a date, got {}", other))),
+    };
+
+    NaiveDate::parse_from_str(&text, "%Y-%m-%d").map_err(D::Error::custom)
+}
+
+pub fn toml_date_opt<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<NaiveDate>, D::Error> {
+    toml_date(deserializer).map(Some)
+}
@@ -0,0 +1,47 @@
+use crate::database::periods::Period;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+
+/* What happens when today is past the period of the active sheet. */
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RolloverMode {
+    /* Offers to move to the sheet of the current period. */
+    #[default]
+    Ask,
+    /* Moves to it without asking. */
+    Auto,
+    /* Keeps the active sheet. */
+    Off,
+}
+
+/*
+ * Moving on to a new sheet once the period of the active one is over, e.g.:
+ *   [rollover]
+ *   mode = "auto"
+ *   name = "%Y-%m"
+ * The new sheet continues the period of the active one, a month after a month, a pay cycle
+ * after a pay cycle. It starts empty: repeating records come from recurring expenses and
+ * installments, categories and budgets from the configuration, and money left in envelopes
+ * moves on through the budget carry-over, as nothing is assigned before income comes in.
+ * 'name' is a strftime template of the sheet name only, filled with the first day of the
+ * new period. The sheet is named after the period when it is not set.
+ */
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct Rollover {
+    #[serde(default)]
+    pub mode: RolloverMode,
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+impl Rollover {
+    /* Name of the sheet of the period, None without a valid template. */
+    pub fn sheet_name(&self, period: &Period) -> Option<String> {
+        let template = self.name.as_ref()?;
+        let mut name = String::new();
+        write!(name, "{}", period.start().format(template)).ok()?;
+
+        (!name.trim().is_empty()).then_some(name)
+    }
+}
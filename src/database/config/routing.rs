@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+
+/*
+ * Sheet new records are added to, e.g.:
+ *   routing = "date"
+ * With routing by date a record goes to the sheet whose period holds its date, a sheet for
+ * it created when there is none.
+ */
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Routing {
+    /* Everything goes to the active sheet. */
+    #[default]
+    Active,
+    Date,
+}
@@ -1,8 +1,11 @@
 use std::path::PathBuf;
 
+use crate::database::config::Goal;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize, Default)]
 pub struct TrackerState {
     pub selected_sheet: Option<PathBuf>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub goals: Vec<Goal>,
 }
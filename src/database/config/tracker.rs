@@ -1,6 +1,9 @@
 use crate::{
-    database::config::TrackerState,
-    database::expense::{ExpenseCategory, ExpensesConfigRaw},
+    database::config::{
+        Account, EnvelopeMode, Goal, Installment, Person, RecurringExpense, Rollover, Routing,
+        TrackerState,
+    },
+    database::expense::{AnomalyThresholds, BudgetRollover, ExpenseCategory, ExpensesConfigRaw},
     error::BtrError,
     utils,
 };
@@ -18,6 +21,40 @@ pub struct TrackerConfig {
     #[serde(skip_deserializing)]
     state: TrackerState,
     expenses: Vec<ExpenseCategory>,
+    #[serde(default)]
+    recurring: Vec<RecurringExpense>,
+    #[serde(default)]
+    installments: Vec<Installment>,
+    #[serde(default)]
+    accounts: Vec<Account>,
+    #[serde(default)]
+    people: Vec<Person>,
+    #[serde(default)]
+    envelopes: Option<EnvelopeMode>,
+    /* Month the fiscal year starts in, January when not set. */
+    #[serde(default)]
+    fiscal_year_start: Option<u32>,
+    #[serde(default)]
+    rollover: Rollover,
+    #[serde(default)]
+    routing: Routing,
+}
+
+impl Default for TrackerConfig {
+    fn default() -> Self {
+        Self {
+            state: TrackerState::default(),
+            expenses: Self::default_cfg(),
+            recurring: Vec::new(),
+            installments: Vec::new(),
+            accounts: Vec::new(),
+            people: Vec::new(),
+            envelopes: None,
+            fiscal_year_start: None,
+            rollover: Rollover::default(),
+            routing: Routing::default(),
+        }
+    }
 }
 
 impl TrackerConfig {
@@ -26,14 +63,23 @@ impl TrackerConfig {
             ExpenseCategory {
                 name: String::from("Groceries"),
                 description: Some(String::from("Groceries and dining")),
+                budget: None,
+                rollover: BudgetRollover::Off,
+                anomaly: AnomalyThresholds::default(),
             },
             ExpenseCategory {
                 name: String::from("Cafe && Bar"),
                 description: Some(String::from("Coffee shops, bars, and related expenses")),
+                budget: None,
+                rollover: BudgetRollover::Off,
+                anomaly: AnomalyThresholds::default(),
             },
             ExpenseCategory {
                 name: String::from("Transport"),
                 description: Some(String::from("Public transport tickets, taxi expenses")),
+                budget: None,
+                rollover: BudgetRollover::Off,
+                anomaly: AnomalyThresholds::default(),
             },
         ]
     }
@@ -81,7 +127,7 @@ impl TrackerConfig {
             BtrError::InvalidData(Some(format!("Failed to parse configuration file: {}", e)))
         })?;
 
-        let expenses = match config.expenses_cfg {
+        let mut tracker_config = match config.expenses_cfg {
             Some(exp_cfg_path) => {
                 let expenses_path = if let Ok(stripped) = exp_cfg_path.strip_prefix("~/") {
                     utils::home_dir().join(stripped)
@@ -92,25 +138,22 @@ impl TrackerConfig {
                 if expenses_path.try_exists()? {
                     let expenses_cfg_str = read_to_string(&expenses_path)?;
 
-                    let parsed_cfg: TrackerConfig =
-                        toml::from_str(&expenses_cfg_str).map_err(|e| {
-                            BtrError::InvalidData(Some(format!(
-                                "Failed to parse expenses configuration file: {}",
-                                e
-                            )))
-                        })?;
-
-                    parsed_cfg.expenses
+                    toml::from_str(&expenses_cfg_str).map_err(|e| {
+                        BtrError::InvalidData(Some(format!(
+                            "Failed to parse expenses configuration file: {}",
+                            e
+                        )))
+                    })?
                 } else {
-                    Self::default_cfg()
+                    Self::default()
                 }
             }
-            None => Self::default_cfg(),
+            None => Self::default(),
         };
 
-        let state = Self::get_state()?;
+        tracker_config.state = Self::get_state()?;
 
-        Ok(Self { state, expenses })
+        Ok(tracker_config)
     }
 
     pub fn update_state<F>(&mut self, updater: F) -> Result<(), BtrError>
@@ -127,7 +170,43 @@ impl TrackerConfig {
         self.state.selected_sheet.as_ref()
     }
 
+    pub fn goals(&self) -> &[Goal] {
+        &self.state.goals
+    }
+
     pub fn expenses(&self) -> &[ExpenseCategory] {
         &self.expenses
     }
+
+    pub fn recurring(&self) -> &[RecurringExpense] {
+        &self.recurring
+    }
+
+    pub fn installments(&self) -> &[Installment] {
+        &self.installments
+    }
+
+    pub fn accounts(&self) -> &[Account] {
+        &self.accounts
+    }
+
+    pub fn people(&self) -> &[Person] {
+        &self.people
+    }
+
+    pub fn envelopes(&self) -> Option<&EnvelopeMode> {
+        self.envelopes.as_ref()
+    }
+
+    pub fn rollover(&self) -> &Rollover {
+        &self.rollover
+    }
+
+    pub fn routing(&self) -> Routing {
+        self.routing
+    }
+
+    pub fn fiscal_year_start(&self) -> u32 {
+        self.fiscal_year_start.unwrap_or(1)
+    }
 }
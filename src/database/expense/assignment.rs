@@ -0,0 +1,10 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+/* Money of a sheet put into the envelope of a category, negative when taken out of it. */
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Assignment {
+    pub category: String,
+    pub amount: f32,
+    pub logged_on: NaiveDate,
+}
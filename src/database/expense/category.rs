@@ -10,4 +10,48 @@ pub struct ExpensesConfigRaw {
 pub struct ExpenseCategory {
     pub name: String,
     pub description: Option<String>,
+    /* Monthly spending limit for the category. */
+    #[serde(default)]
+    pub budget: Option<f32>,
+    #[serde(default)]
+    pub rollover: BudgetRollover,
+    #[serde(default)]
+    pub anomaly: AnomalyThresholds,
+}
+
+/* What part of the budget left at the end of a sheet is carried into the next one. */
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum BudgetRollover {
+    #[default]
+    Off,
+    /* Only the unused budget, overspending is forgotten. */
+    Unused,
+    /* The unused budget and the overspending, which lowers the next budget. */
+    All,
+}
+
+/* Limits past which an expense of the category is reported as unusual. */
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct AnomalyThresholds {
+    /* Expense bigger than this many times the category median. */
+    pub median_ratio: f32,
+    /* Expense at a never seen payee bigger than this many times the category median. */
+    pub new_payee_ratio: f32,
+    /* Period total bigger than this many times the average of the previous months. */
+    pub monthly_jump: f32,
+    /* Expenses below this amount are never reported. */
+    pub min_amount: f32,
+}
+
+impl Default for AnomalyThresholds {
+    fn default() -> Self {
+        Self {
+            median_ratio: 3.0,
+            new_payee_ratio: 2.0,
+            monthly_jump: 1.5,
+            min_amount: 0.0,
+        }
+    }
 }
@@ -0,0 +1,39 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+/* Category of the records moving money of a debt. They are not counted as spending. */
+pub const DEBT_CATEGORY: &str = "Debt";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DebtKind {
+    /* Money given to the counterparty, to be paid back. */
+    Lent,
+    /* Money received from the counterparty, to be paid back. */
+    Borrowed,
+}
+
+impl DebtKind {
+    /* Direction of the money leaving an account when the debt is taken: 1 out, -1 in. */
+    pub fn outflow(self) -> f32 {
+        match self {
+            DebtKind::Lent => 1.0,
+            DebtKind::Borrowed => -1.0,
+        }
+    }
+}
+
+/* A loan given or taken, paid back by the expense records linked to it by the ID. */
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Debt {
+    pub id: u64,
+    pub counterparty: String,
+    pub kind: DebtKind,
+    pub principal: f32,
+    /* Simple annual interest in percent, accrued on the unpaid principal. */
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interest: Option<f32>,
+    pub logged_on: NaiveDate,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
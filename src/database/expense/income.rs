@@ -0,0 +1,67 @@
+use crate::database::expense::ClearStatus;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct IncomeRecord {
+    source: String,
+    amount: f32,
+    logged_on: NaiveDate,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    note: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    account: Option<String>,
+    #[serde(default, skip_serializing_if = "ClearStatus::is_pending")]
+    status: ClearStatus,
+}
+
+impl IncomeRecord {
+    pub fn new(source: String, amount: f32, logged_on: NaiveDate) -> Self {
+        Self {
+            source,
+            amount,
+            logged_on,
+            note: None,
+            account: None,
+            status: ClearStatus::Pending,
+        }
+    }
+
+    pub fn with_note(mut self, note: Option<String>) -> Self {
+        self.note = note;
+        self
+    }
+
+    pub fn with_account(mut self, account: Option<String>) -> Self {
+        self.account = account;
+        self
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn amount(&self) -> f32 {
+        self.amount
+    }
+
+    pub fn logged_on(&self) -> NaiveDate {
+        self.logged_on
+    }
+
+    pub fn note(&self) -> Option<&str> {
+        self.note.as_deref()
+    }
+
+    pub fn account(&self) -> Option<&str> {
+        self.account.as_deref()
+    }
+
+    pub fn status(&self) -> ClearStatus {
+        self.status
+    }
+
+    pub fn set_status(&mut self, status: ClearStatus) {
+        self.status = status;
+    }
+}
@@ -1,8 +1,20 @@
+mod assignment;
 mod category;
+mod debt;
+mod income;
 mod record;
+mod shared;
 mod sheet;
+mod status;
+mod transfer;
 
 /* Re-exports */
-pub use category::{ExpenseCategory, ExpensesConfigRaw};
-pub use record::ExpenseRecord;
+pub use assignment::Assignment;
+pub use category::{AnomalyThresholds, BudgetRollover, ExpenseCategory, ExpensesConfigRaw};
+pub use debt::{DEBT_CATEGORY, Debt, DebtKind};
+pub use income::IncomeRecord;
+pub use record::{ExpenseRecord, SplitLine};
+pub use shared::{Settlement, SharedExpense, Split};
 pub use sheet::ExpenseSheet;
+pub use status::ClearStatus;
+pub use transfer::TransferRecord;
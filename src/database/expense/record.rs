@@ -1,20 +1,217 @@
+use crate::{
+    database::expense::{ClearStatus, DEBT_CATEGORY, Debt, SharedExpense},
+    error::BtrError,
+};
 use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Debug)]
+/* Part of a split expense attributed to its own category. */
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SplitLine {
+    pub category: String,
+    pub amount: f32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ExpenseRecord {
+    /* Assigned once another record refers to this one. */
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    id: Option<u64>,
     category: String,
+    /* Negative for a refund. */
     amount: f32,
     logged_on: NaiveDate,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    payee: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    note: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    account: Option<String>,
+    #[serde(default, skip_serializing_if = "ClearStatus::is_pending")]
+    status: ClearStatus,
+    /* Payer and split of an expense shared with other people. */
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    shared: Option<SharedExpense>,
+    /* ID of the expense a refund returns money for. */
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    refund_of: Option<u64>,
+    /* Lines of a split expense, summing up to the amount. */
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    lines: Vec<SplitLine>,
+    /* ID of the debt the money was lent, borrowed or paid back for. */
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    debt: Option<u64>,
 }
 
 impl ExpenseRecord {
     pub fn new(category: String, amount: f32, logged_on: NaiveDate) -> Self {
         Self {
+            id: None,
             category,
             amount,
             logged_on,
+            payee: None,
+            note: None,
+            tags: Vec::new(),
+            account: None,
+            status: ClearStatus::Pending,
+            shared: None,
+            refund_of: None,
+            lines: Vec::new(),
+            debt: None,
+        }
+    }
+
+    /* An expense split into lines, filed under the category of the first one. */
+    pub fn split(lines: Vec<SplitLine>, logged_on: NaiveDate) -> Result<Self, BtrError> {
+        let Some(first) = lines.first() else {
+            return Err(BtrError::InvalidData(Some(String::from(
+                "A split expense needs at least one line.",
+            ))));
+        };
+        if lines.iter().any(|line| line.amount <= 0.0) {
+            return Err(BtrError::InvalidData(Some(String::from(
+                "The amount of every line must be greater than 0.",
+            ))));
+        }
+
+        let amount = lines.iter().fold(0.0, |sum, line| sum + line.amount);
+        let mut record = Self::new(first.category.clone(), amount, logged_on);
+        record.lines = lines;
+        Ok(record)
+    }
+
+    /*
+     * A refund of 'amount' for the expense with the given ID, in the same category. A refund
+     * of a split expense is split across its lines in proportion to them.
+     */
+    pub fn refund(original: &ExpenseRecord, id: u64, amount: f32, logged_on: NaiveDate) -> Self {
+        let mut refund = Self::new(original.category.clone(), -amount, logged_on)
+            .with_payee(original.payee.clone())
+            .with_account(original.account.clone());
+        refund.refund_of = Some(id);
+
+        let mut left = amount;
+        refund.lines = original
+            .lines
+            .iter()
+            .enumerate()
+            .map(|(idx, line)| {
+                /* The last line takes what rounding leaves, so the lines add up to the refund. */
+                let part = if idx + 1 == original.lines.len() {
+                    left
+                } else {
+                    (line.amount / original.amount * amount * 100.0).round() / 100.0
+                };
+                left -= part;
+                SplitLine {
+                    amount: -part,
+                    ..line.clone()
+                }
+            })
+            .collect();
+        refund
+    }
+
+    /* Money of a debt, positive when leaving an account and negative when coming in. */
+    pub fn debt_flow(debt: &Debt, amount: f32, logged_on: NaiveDate) -> Self {
+        let mut record = Self::new(DEBT_CATEGORY.to_string(), amount, logged_on)
+            .with_payee(Some(debt.counterparty.clone()));
+        record.debt = Some(debt.id);
+        record
+    }
+
+    pub fn with_payee(mut self, payee: Option<String>) -> Self {
+        self.payee = payee;
+        self
+    }
+
+    pub fn with_note(mut self, note: Option<String>) -> Self {
+        self.note = note;
+        self
+    }
+
+    pub fn with_tags(mut self, tags: Vec<String>) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    pub fn with_account(mut self, account: Option<String>) -> Self {
+        self.account = account;
+        self
+    }
+
+    pub fn with_shared(mut self, shared: Option<SharedExpense>) -> Self {
+        self.shared = shared;
+        self
+    }
+
+    pub fn id(&self) -> Option<u64> {
+        self.id
+    }
+
+    pub fn set_id(&mut self, id: u64) {
+        self.id = Some(id);
+    }
+
+    pub fn refund_of(&self) -> Option<u64> {
+        self.refund_of
+    }
+
+    pub fn is_refund(&self) -> bool {
+        self.refund_of.is_some()
+    }
+
+    pub fn debt(&self) -> Option<u64> {
+        self.debt
+    }
+
+    /* Lending, borrowing and repaying is not spending. */
+    pub fn is_debt(&self) -> bool {
+        self.debt.is_some()
+    }
+
+    pub fn lines(&self) -> &[SplitLine] {
+        &self.lines
+    }
+
+    /* Category and amount of every split line, or of the record itself when not split. */
+    pub fn parts(&self) -> Vec<(&str, f32)> {
+        if self.lines.is_empty() {
+            vec![(self.category.as_str(), self.amount)]
+        } else {
+            self.lines
+                .iter()
+                .map(|line| (line.category.as_str(), line.amount))
+                .collect()
+        }
+    }
+
+    /* One record per split line, the record itself when not split. */
+    pub fn itemized(&self) -> Vec<ExpenseRecord> {
+        if self.lines.is_empty() {
+            return vec![self.clone()];
         }
+
+        self.lines
+            .iter()
+            .map(|line| {
+                let mut part = self.clone();
+                part.category = line.category.clone();
+                part.amount = line.amount;
+                part.note = line.note.clone().or_else(|| self.note.clone());
+                part.lines = Vec::new();
+                part
+            })
+            .collect()
+    }
+
+    pub fn itemize(records: &[ExpenseRecord]) -> Vec<ExpenseRecord> {
+        records.iter().flat_map(ExpenseRecord::itemized).collect()
     }
 
     pub fn category(&self) -> &str {
@@ -28,4 +225,32 @@ impl ExpenseRecord {
     pub fn logged_on(&self) -> NaiveDate {
         self.logged_on
     }
+
+    pub fn payee(&self) -> Option<&str> {
+        self.payee.as_deref()
+    }
+
+    pub fn note(&self) -> Option<&str> {
+        self.note.as_deref()
+    }
+
+    pub fn tags(&self) -> &[String] {
+        &self.tags
+    }
+
+    pub fn account(&self) -> Option<&str> {
+        self.account.as_deref()
+    }
+
+    pub fn shared(&self) -> Option<&SharedExpense> {
+        self.shared.as_ref()
+    }
+
+    pub fn status(&self) -> ClearStatus {
+        self.status
+    }
+
+    pub fn set_status(&mut self, status: ClearStatus) {
+        self.status = status;
+    }
 }
@@ -0,0 +1,79 @@
+use crate::error::BtrError;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/* How the amount of a shared expense is divided. */
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum Split {
+    Equal { people: Vec<String> },
+    Percent { shares: BTreeMap<String, f32> },
+    Exact { shares: BTreeMap<String, f32> },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SharedExpense {
+    pub payer: String,
+    pub split: Split,
+}
+
+impl SharedExpense {
+    /* Part of the amount every person owes, the payer's own share included. */
+    pub fn shares(&self, amount: f32) -> Vec<(String, f32)> {
+        match &self.split {
+            Split::Equal { people } => people
+                .iter()
+                .map(|person| (person.clone(), amount / people.len() as f32))
+                .collect(),
+            Split::Percent { shares } => shares
+                .iter()
+                .map(|(person, percent)| (person.clone(), amount * percent / 100.0))
+                .collect(),
+            Split::Exact { shares } => shares
+                .iter()
+                .map(|(person, share)| (person.clone(), *share))
+                .collect(),
+        }
+    }
+
+    /* The split must name someone and account for the whole amount. */
+    pub fn validate(&self, amount: f32) -> Result<(), BtrError> {
+        let invalid = |message: String| Err(BtrError::InvalidData(Some(message)));
+
+        match &self.split {
+            Split::Equal { people } if people.is_empty() => {
+                invalid(String::from("An equal split needs at least one person."))
+            }
+            Split::Percent { shares } => {
+                let total: f32 = shares.values().sum();
+                if (total - 100.0).abs() > 0.01 {
+                    invalid(format!("Percentages sum up to {:.2}, not 100.", total))
+                } else {
+                    Ok(())
+                }
+            }
+            Split::Exact { shares } => {
+                let total: f32 = shares.values().sum();
+                if (total - amount).abs() > 0.005 {
+                    invalid(format!(
+                        "Exact shares sum up to {:.2}, not {:.2}.",
+                        total, amount
+                    ))
+                } else {
+                    Ok(())
+                }
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/* Money paid back between two people. Not an expense. */
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Settlement {
+    pub from: String,
+    pub to: String,
+    pub amount: f32,
+    pub logged_on: NaiveDate,
+}
@@ -1,19 +1,39 @@
 use crate::{
     database::{
-        expense::ExpenseRecord,
-        periods::{self, Period},
+        expense::{Assignment, Debt, ExpenseRecord, IncomeRecord, Settlement, TransferRecord},
+        periods::Period,
     },
     error::BtrError,
     utils,
 };
+use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 use std::fs::write;
 
-#[derive(Serialize, Deserialize, Debug)]
+/* A scheduled charge already added to the sheet, remembered so it is never added twice. */
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AppliedCharge {
+    pub source: String,
+    pub date: NaiveDate,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ExpenseSheet {
     pub name: String, /* TODO: Try to modify to &str */
     pub period: Period,
     expenses: Vec<ExpenseRecord>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    income: Vec<IncomeRecord>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    transfers: Vec<TransferRecord>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    settlements: Vec<Settlement>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    debts: Vec<Debt>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    assignments: Vec<Assignment>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    applied: Vec<AppliedCharge>,
 }
 
 impl ExpenseSheet {
@@ -22,6 +42,12 @@ impl ExpenseSheet {
             name,
             period,
             expenses,
+            income: Vec::new(),
+            transfers: Vec::new(),
+            settlements: Vec::new(),
+            debts: Vec::new(),
+            assignments: Vec::new(),
+            applied: Vec::new(),
         }
     }
 
@@ -55,4 +81,65 @@ impl ExpenseSheet {
     pub fn expenses_mut(&mut self) -> &mut Vec<ExpenseRecord> {
         &mut self.expenses
     }
+
+    pub fn income(&self) -> &[IncomeRecord] {
+        &self.income
+    }
+
+    pub fn income_mut(&mut self) -> &mut Vec<IncomeRecord> {
+        &mut self.income
+    }
+
+    pub fn transfers(&self) -> &[TransferRecord] {
+        &self.transfers
+    }
+
+    pub fn transfers_mut(&mut self) -> &mut Vec<TransferRecord> {
+        &mut self.transfers
+    }
+
+    pub fn settlements(&self) -> &[Settlement] {
+        &self.settlements
+    }
+
+    pub fn settlements_mut(&mut self) -> &mut Vec<Settlement> {
+        &mut self.settlements
+    }
+
+    pub fn debts(&self) -> &[Debt] {
+        &self.debts
+    }
+
+    pub fn debts_mut(&mut self) -> &mut Vec<Debt> {
+        &mut self.debts
+    }
+
+    pub fn assignments(&self) -> &[Assignment] {
+        &self.assignments
+    }
+
+    pub fn assignments_mut(&mut self) -> &mut Vec<Assignment> {
+        &mut self.assignments
+    }
+
+    pub fn is_applied(&self, source: &str, date: NaiveDate) -> bool {
+        self.applied
+            .iter()
+            .any(|charge| charge.source == source && charge.date == date)
+    }
+
+    /* Adds a scheduled charge unless it was added before. Returns false for a repeated one. */
+    pub fn apply_charge(&mut self, source: &str, record: ExpenseRecord) -> bool {
+        let date = record.logged_on();
+        if self.is_applied(source, date) {
+            return false;
+        }
+
+        self.applied.push(AppliedCharge {
+            source: source.to_string(),
+            date,
+        });
+        self.expenses.push(record);
+        true
+    }
 }
@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+
+/* Whether a record was matched with a bank statement. */
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ClearStatus {
+    #[default]
+    Pending,
+    Cleared,
+}
+
+impl ClearStatus {
+    pub fn is_pending(&self) -> bool {
+        *self == ClearStatus::Pending
+    }
+}
@@ -0,0 +1,74 @@
+use crate::database::expense::ClearStatus;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+/* Money moved between two accounts. Neither an expense nor an income. */
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TransferRecord {
+    from: String,
+    to: String,
+    amount: f32,
+    logged_on: NaiveDate,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    note: Option<String>,
+    /* Each side of a transfer clears on its own statement. */
+    #[serde(default, skip_serializing_if = "ClearStatus::is_pending")]
+    from_status: ClearStatus,
+    #[serde(default, skip_serializing_if = "ClearStatus::is_pending")]
+    to_status: ClearStatus,
+}
+
+impl TransferRecord {
+    pub fn new(from: String, to: String, amount: f32, logged_on: NaiveDate) -> Self {
+        Self {
+            from,
+            to,
+            amount,
+            logged_on,
+            note: None,
+            from_status: ClearStatus::Pending,
+            to_status: ClearStatus::Pending,
+        }
+    }
+
+    pub fn with_note(mut self, note: Option<String>) -> Self {
+        self.note = note;
+        self
+    }
+
+    pub fn from(&self) -> &str {
+        &self.from
+    }
+
+    pub fn to(&self) -> &str {
+        &self.to
+    }
+
+    pub fn amount(&self) -> f32 {
+        self.amount
+    }
+
+    pub fn logged_on(&self) -> NaiveDate {
+        self.logged_on
+    }
+
+    pub fn note(&self) -> Option<&str> {
+        self.note.as_deref()
+    }
+
+    pub fn outgoing_status(&self) -> ClearStatus {
+        self.from_status
+    }
+
+    pub fn incoming_status(&self) -> ClearStatus {
+        self.to_status
+    }
+
+    pub fn set_outgoing_status(&mut self, status: ClearStatus) {
+        self.from_status = status;
+    }
+
+    pub fn set_incoming_status(&mut self, status: ClearStatus) {
+        self.to_status = status;
+    }
+}
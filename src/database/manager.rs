@@ -1,3 +1,4 @@
+use chrono::NaiveDate;
 use std::{
     fs::{File, create_dir_all, read_to_string},
     io::Write,
@@ -5,9 +6,15 @@ use std::{
 
 use crate::{
     database::{
-        config::TrackerConfig,
-        expense::{ExpenseCategory, ExpenseSheet},
+        config::{
+            Account, EnvelopeMode, Goal, Installment, Person, Rollover, Routing, TrackerConfig,
+        },
+        expense::{
+            Debt, ExpenseCategory, ExpenseRecord, ExpenseSheet, IncomeRecord, Settlement,
+            TransferRecord,
+        },
         periods::Period,
+        query::RecordQuery,
     },
     error::BtrError,
     utils,
@@ -26,7 +33,7 @@ impl TrackerManager {
         };
 
         let active_sheet = if let Some(active_sheet_path) = config.load_active_sheet() {
-            let active_sheet_str = read_to_string(&active_sheet_path)?;
+            let active_sheet_str = read_to_string(active_sheet_path)?;
 
             serde_json::from_str::<ExpenseSheet>(&active_sheet_str).ok()
         } else {
@@ -75,15 +82,7 @@ impl TrackerManager {
         let new_active_sheet = match sheet_name {
             Some(sheet) => {
                 let sheet_path = utils::sheets_dir().join(format!("{}.json", sheet));
-
-                let sheet_content = read_to_string(&sheet_path)?;
-                let active_sheet: ExpenseSheet =
-                    serde_json::from_str(&sheet_content).map_err(|e| {
-                        BtrError::InvalidData(Some(format!(
-                            "Failed to deserialize a sheet data: {}",
-                            e
-                        )))
-                    })?;
+                let active_sheet = Self::load_sheet(sheet)?;
 
                 self.config
                     .update_state(|state| state.selected_sheet = Some(sheet_path))?;
@@ -109,6 +108,47 @@ impl TrackerManager {
         self.config.expenses()
     }
 
+    pub fn get_accounts(&self) -> &[Account] {
+        self.config.accounts()
+    }
+
+    pub fn get_people(&self) -> &[Person] {
+        self.config.people()
+    }
+
+    pub fn get_installments(&self) -> &[Installment] {
+        self.config.installments()
+    }
+
+    /* Parses a period spec, fiscal years starting in the configured month. */
+    pub fn parse_period(&self, spec: &str) -> Result<Period, BtrError> {
+        Period::parse(spec, self.config.fiscal_year_start())
+    }
+
+    pub fn get_envelope_mode(&self) -> Option<&EnvelopeMode> {
+        self.config.envelopes()
+    }
+
+    pub fn get_rollover(&self) -> &Rollover {
+        self.config.rollover()
+    }
+
+    pub fn get_routing(&self) -> Routing {
+        self.config.routing()
+    }
+
+    pub fn get_goals(&self) -> &[Goal] {
+        self.config.goals()
+    }
+
+    /* Goals are a part of the tracker state, saved on every change. */
+    pub fn update_goals<F>(&mut self, updater: F) -> Result<(), BtrError>
+    where
+        F: FnOnce(&mut Vec<Goal>),
+    {
+        self.config.update_state(|state| updater(&mut state.goals))
+    }
+
     pub fn update_active_sheet<F>(&mut self, updater: F) -> Result<(), BtrError>
     where
         F: FnOnce(&mut ExpenseSheet),
@@ -121,4 +161,139 @@ impl TrackerManager {
         sheet.update(updater)?;
         Ok(())
     }
+
+    pub fn load_sheet(sheet_name: &str) -> Result<ExpenseSheet, BtrError> {
+        let sheet_path = utils::sheets_dir().join(format!("{}.json", sheet_name));
+
+        let sheet_content = read_to_string(&sheet_path)?;
+        serde_json::from_str(&sheet_content).map_err(|e| {
+            BtrError::InvalidData(Some(format!("Failed to deserialize a sheet data: {}", e)))
+        })
+    }
+
+    pub fn load_sheets(&self) -> Result<Vec<ExpenseSheet>, BtrError> {
+        let sheet_dir = utils::sheets_dir();
+        if !sheet_dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut sheets = Vec::new();
+        for entry in sheet_dir.read_dir()? {
+            let path = entry?.path();
+            if !path.is_file() || path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+
+            let sheet_str = read_to_string(&path)?;
+            let sheet: ExpenseSheet = serde_json::from_str(&sheet_str).map_err(|e| {
+                BtrError::InvalidData(Some(format!(
+                    "Failed to deserialize sheet '{}': {}",
+                    path.display(),
+                    e
+                )))
+            })?;
+
+            sheets.push(sheet);
+        }
+
+        Ok(sheets)
+    }
+
+    /*
+     * The sheet preceded by the sheets before it, oldest first. Each earlier sheet is the
+     * latest one ending before the next one starts, so sheets overlapping them are skipped.
+     */
+    pub fn sheet_chain(&self, sheet: &ExpenseSheet) -> Result<Vec<ExpenseSheet>, BtrError> {
+        let mut earlier = self.load_sheets()?;
+        earlier.sort_by_key(|s| *s.period.end());
+
+        let mut chain = vec![sheet.clone()];
+        let mut start = *sheet.period.start();
+        while let Some(pos) = earlier.iter().rposition(|s| *s.period.end() < start) {
+            earlier.truncate(pos + 1);
+            let Some(previous) = earlier.pop() else {
+                break;
+            };
+            start = *previous.period.start();
+            chain.push(previous);
+        }
+
+        chain.reverse();
+        Ok(chain)
+    }
+
+    /* Saves a sheet loaded from disk, keeping the active sheet in sync with it. */
+    pub fn save_sheet(&mut self, sheet: ExpenseSheet) -> Result<(), BtrError> {
+        sheet.save_sheet()?;
+
+        if let Some(active_sheet) = self.active_sheet.as_mut()
+            && active_sheet.name == sheet.name
+        {
+            *active_sheet = sheet;
+        }
+
+        Ok(())
+    }
+
+    /*
+     * Adds the recurring expenses and installment payments due by 'today' within the sheet's
+     * period. Charges added before are skipped, also when the user deleted them since.
+     */
+    pub fn apply_scheduled(
+        &mut self,
+        sheet_name: &str,
+        today: NaiveDate,
+    ) -> Result<Vec<ExpenseRecord>, BtrError> {
+        let mut sheet = Self::load_sheet(sheet_name)?;
+
+        let mut added = Vec::new();
+        for recurring in self.config.recurring() {
+            for date in recurring.occurrences(&sheet.period) {
+                let record = recurring.to_record(date);
+                if date <= today && sheet.apply_charge(&recurring.source(), record.clone()) {
+                    added.push(record);
+                }
+            }
+        }
+        for installment in self.config.installments() {
+            for (date, amount) in installment.charges(&sheet.period) {
+                let record = installment.to_record(date, amount);
+                if date <= today && sheet.apply_charge(&installment.source(), record.clone()) {
+                    added.push(record);
+                }
+            }
+        }
+
+        if !added.is_empty() {
+            self.save_sheet(sheet)?;
+        }
+
+        added.sort_by_key(|record| record.logged_on());
+        Ok(added)
+    }
+
+    /* Runs the query over every sheet under the sheets directory. */
+    pub fn query(&self, query: &RecordQuery) -> Result<Vec<ExpenseRecord>, BtrError> {
+        Ok(query.run(&self.load_sheets()?))
+    }
+
+    /* Income from every sheet within the range of the query. */
+    pub fn query_income(&self, query: &RecordQuery) -> Result<Vec<IncomeRecord>, BtrError> {
+        Ok(query.run_income(&self.load_sheets()?))
+    }
+
+    /* Transfers from every sheet within the range of the query. */
+    pub fn query_transfers(&self, query: &RecordQuery) -> Result<Vec<TransferRecord>, BtrError> {
+        Ok(query.run_transfers(&self.load_sheets()?))
+    }
+
+    /* Settlements between people from every sheet within the range of the query. */
+    pub fn query_settlements(&self, query: &RecordQuery) -> Result<Vec<Settlement>, BtrError> {
+        Ok(query.run_settlements(&self.load_sheets()?))
+    }
+
+    /* Debts taken in every sheet within the range of the query. */
+    pub fn query_debts(&self, query: &RecordQuery) -> Result<Vec<Debt>, BtrError> {
+        Ok(query.run_debts(&self.load_sheets()?))
+    }
 }
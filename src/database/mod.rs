@@ -2,3 +2,4 @@ pub mod config;
 pub mod expense;
 pub mod manager;
 pub mod periods;
+pub mod query;
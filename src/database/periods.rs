@@ -1,13 +1,36 @@
 use crate::error::BtrError;
-use chrono::{Datelike, NaiveDate, Utc};
+use chrono::{Datelike, Duration, Months, NaiveDate, Utc, Weekday};
 use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
+
+/* Length of the sub-periods a period is split into. */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+    Day,
+    /* ISO weeks, Monday to Sunday. */
+    Week,
+    /* Calendar months. */
+    Month,
+}
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Period {
     start: NaiveDate,
     end: NaiveDate,
+    /* Day of the month a pay cycle starts on, kept for stepping to the next cycles. */
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    payday: Option<u32>,
+}
+
+/* Periods covering the same days are equal, whether they keep a payday or not. */
+impl PartialEq for Period {
+    fn eq(&self, other: &Self) -> bool {
+        self.start == other.start && self.end == other.end
+    }
 }
 
+impl Eq for Period {}
+
 impl Period {
     pub fn new(start: NaiveDate, end: NaiveDate) -> Result<Self, BtrError> {
         if start > end {
@@ -16,7 +39,11 @@ impl Period {
             )));
         }
 
-        Ok(Self { start, end })
+        Ok(Self {
+            start,
+            end,
+            payday: None,
+        })
     }
 
     pub fn current_month() -> Result<Self, BtrError> {
@@ -61,6 +88,272 @@ impl Period {
         Self::new(start, end)
     }
 
+    /* Quarter 1 to 4 of the year. */
+    pub fn quarter(quarter: u32, year: i32) -> Result<Self, BtrError> {
+        if !(1..=4).contains(&quarter) {
+            return Err(BtrError::InvalidPeriod(String::from(
+                "Quarter must be in range from 1 to 4.",
+            )));
+        }
+
+        let start =
+            NaiveDate::from_ymd_opt(year, quarter * 3 - 2, 1).ok_or(BtrError::InvalidData(None))?;
+        Self::months_from(start, 3)
+    }
+
+    /* ISO 8601 week, Monday to Sunday. The first week of a year holds its first Thursday. */
+    pub fn week(week: u32, year: i32) -> Result<Self, BtrError> {
+        let start = NaiveDate::from_isoywd_opt(year, week, Weekday::Mon).ok_or_else(|| {
+            BtrError::InvalidPeriod(format!("Year {} has no week {}.", year, week))
+        })?;
+
+        Self::new(start, start + Duration::days(6))
+    }
+
+    /* Twelve months starting on the first day of 'start_month' of the year. */
+    pub fn fiscal_year(year: i32, start_month: u32) -> Result<Self, BtrError> {
+        if !(1..=12).contains(&start_month) {
+            return Err(BtrError::InvalidPeriod(String::from(
+                "Fiscal year must start in a month from 1 to 12.",
+            )));
+        }
+
+        let start =
+            NaiveDate::from_ymd_opt(year, start_month, 1).ok_or(BtrError::InvalidData(None))?;
+        Self::months_from(start, 12)
+    }
+
+    /*
+     * Pay cycle containing the date, from a payday to the day before the next one, e.g. the
+     * 25th to the 24th. Paydays past the end of a shorter month fall on its last day.
+     */
+    pub fn pay_cycle(payday: u32, date: NaiveDate) -> Result<Self, BtrError> {
+        if !(1..=31).contains(&payday) {
+            return Err(BtrError::InvalidPeriod(String::from(
+                "Payday must be in range from 1 to 31.",
+            )));
+        }
+
+        let payday_in = |month: NaiveDate| -> Result<NaiveDate, BtrError> {
+            let last_day = Self::month(month.month(), month.year())?.end.day();
+            NaiveDate::from_ymd_opt(month.year(), month.month(), payday.min(last_day))
+                .ok_or(BtrError::InvalidData(None))
+        };
+        let mut start = payday_in(date)?;
+        if start > date {
+            let last_month = date
+                .checked_sub_months(Months::new(1))
+                .ok_or(BtrError::InvalidData(None))?;
+            start = payday_in(last_month)?;
+        }
+        let next_month = start
+            .checked_add_months(Months::new(1))
+            .ok_or(BtrError::InvalidData(None))?;
+        let next = payday_in(next_month)?;
+
+        Ok(Self {
+            payday: Some(payday),
+            ..Self::new(start, next - Duration::days(1))?
+        })
+    }
+
+    /* 'count' months from the start, the last one ending the day before the next would start. */
+    fn months_from(start: NaiveDate, count: u32) -> Result<Self, BtrError> {
+        let end = start
+            .checked_add_months(Months::new(count))
+            .and_then(|d| d.pred_opt())
+            .ok_or(BtrError::InvalidData(None))?;
+
+        Self::new(start, end)
+    }
+
+    /*
+     * Number of whole months the period spans exactly, e.g. 1 for a month or a pay cycle
+     * from the 25th to the 24th, 3 for a quarter.
+     */
+    fn whole_months(&self) -> Option<u32> {
+        let months = (self.end.year() - self.start.year()) * 12 + self.end.month() as i32
+            - self.start.month() as i32;
+
+        /* A period starting later in the month ends a day before the same day of a month. */
+        [months, months + 1]
+            .into_iter()
+            .filter_map(|m| u32::try_from(m).ok().filter(|m| *m > 0))
+            .find(|&m| Self::months_from(self.start, m).is_ok_and(|p| p.end == self.end))
+    }
+
+    /*
+     * The period right before this one: the previous pay cycle, as many whole months as it
+     * spans or as many days.
+     */
+    pub fn prev(&self) -> Result<Self, BtrError> {
+        if let Some(payday) = self.payday {
+            return Self::pay_cycle(payday, self.start - Duration::days(1));
+        }
+
+        match self.whole_months() {
+            Some(months) => Self::months_from(
+                self.start
+                    .checked_sub_months(Months::new(months))
+                    .ok_or(BtrError::InvalidData(None))?,
+                months,
+            ),
+            None => {
+                let length = self.end - self.start + Duration::days(1);
+                Self::new(self.start - length, self.start - Duration::days(1))
+            }
+        }
+    }
+
+    /*
+     * The period right after this one: the next pay cycle, as many whole months as it spans
+     * or as many days.
+     */
+    pub fn next(&self) -> Result<Self, BtrError> {
+        if let Some(payday) = self.payday {
+            return Self::pay_cycle(payday, self.end + Duration::days(1));
+        }
+
+        match self.whole_months() {
+            Some(months) => Self::months_from(
+                self.start
+                    .checked_add_months(Months::new(months))
+                    .ok_or(BtrError::InvalidData(None))?,
+                months,
+            ),
+            None => {
+                let length = self.end - self.start + Duration::days(1);
+                Self::new(self.end + Duration::days(1), self.end + length)
+            }
+        }
+    }
+
+    /* The same period a year earlier. */
+    pub fn year_before(&self) -> Result<Self, BtrError> {
+        let year = Months::new(12);
+        let start = self
+            .start
+            .checked_sub_months(year)
+            .ok_or(BtrError::InvalidData(None))?;
+
+        if let Some(payday) = self.payday {
+            return Self::pay_cycle(payday, start);
+        }
+
+        match self.whole_months() {
+            Some(months) => Self::months_from(start, months),
+            None => Self::new(
+                start,
+                self.end
+                    .checked_sub_months(year)
+                    .ok_or(BtrError::InvalidData(None))?,
+            ),
+        }
+    }
+
+    /*
+     * Parses a period spec. Besides the forms of 'from_str' accepts "FY2025", the fiscal
+     * year starting in 2025 on the first day of 'fiscal_start'.
+     */
+    pub fn parse(spec: &str, fiscal_start: u32) -> Result<Self, BtrError> {
+        let invalid = || BtrError::InvalidPeriod(format!("Unsupported period spec '{}'", spec));
+        let spec = spec.trim();
+
+        match spec {
+            "month" => return Self::current_month(),
+            "year" => return Self::current_year(),
+            _ => {}
+        }
+
+        let date = |text: &str| NaiveDate::parse_from_str(text, "%Y-%m-%d").map_err(|_| invalid());
+
+        if let Some((start, end)) = spec.split_once("..") {
+            return Self::new(date(start)?, date(end)?);
+        }
+        if let Some(payday) = spec.strip_prefix("payday:") {
+            return match payday.split_once(':') {
+                Some((day, on)) => Self::pay_cycle(day.parse().map_err(|_| invalid())?, date(on)?),
+                None => Self::pay_cycle(
+                    payday.parse().map_err(|_| invalid())?,
+                    Utc::now().date_naive(),
+                ),
+            };
+        }
+        if let Some(year) = spec.strip_prefix("FY").or_else(|| spec.strip_prefix("fy")) {
+            return Self::fiscal_year(year.parse().map_err(|_| invalid())?, fiscal_start);
+        }
+        if let Ok(day) = date(spec) {
+            return Self::new(day, day);
+        }
+
+        match spec.split_once('-') {
+            None => Self::year(spec.parse().map_err(|_| invalid())?),
+            Some((a, b)) if a.len() == 4 => {
+                let year = a.parse().map_err(|_| invalid())?;
+                match b.split_at_checked(1) {
+                    Some(("Q" | "q", quarter)) => {
+                        Self::quarter(quarter.parse().map_err(|_| invalid())?, year)
+                    }
+                    Some(("W" | "w", week)) => {
+                        Self::week(week.parse().map_err(|_| invalid())?, year)
+                    }
+                    _ => Self::month(b.parse().map_err(|_| invalid())?, year),
+                }
+            }
+            Some((a, b)) => Self::month(
+                a.parse().map_err(|_| invalid())?,
+                b.parse().map_err(|_| invalid())?,
+            ),
+        }
+    }
+
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.start <= date && date <= self.end
+    }
+
+    pub fn overlaps(&self, other: &Period) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+
+    /* Days common to both periods, if any. */
+    pub fn intersection(&self, other: &Period) -> Option<Period> {
+        Self::new(self.start.max(other.start), self.end.min(other.end)).ok()
+    }
+
+    /* Number of days, both ends included. */
+    pub fn days(&self) -> i64 {
+        (self.end - self.start).num_days() + 1
+    }
+
+    /*
+     * Consecutive sub-periods covering the period. Weeks and months follow the calendar, so
+     * the first and the last one are cut to the period when it starts or ends midway.
+     */
+    pub fn split(&self, step: Step) -> Vec<Period> {
+        let mut parts = Vec::new();
+        let mut cursor = Some(self.start);
+
+        while let Some(start) = cursor.filter(|date| *date <= self.end) {
+            let end = match step {
+                Step::Day => start,
+                Step::Week => {
+                    start + Duration::days(6 - start.weekday().num_days_from_monday() as i64)
+                }
+                Step::Month => Self::month(start.month(), start.year()).map_or(self.end, |m| m.end),
+            }
+            .min(self.end);
+
+            parts.push(Period {
+                start,
+                end,
+                payday: None,
+            });
+            cursor = end.succ_opt();
+        }
+
+        parts
+    }
+
     pub fn start(&self) -> &NaiveDate {
         &self.start
     }
@@ -69,3 +362,204 @@ impl Period {
         &self.end
     }
 }
+
+impl FromStr for Period {
+    type Err = BtrError;
+
+    /*
+     * Accepts "month", "year", a year ("2025"), a month ("2025-03" or "03-2025"), a quarter
+     * ("2025-Q3"), an ISO week ("2025-W14"), a day ("2025-03-10"), a range of days
+     * ("2025-03-10..2025-04-09") or the pay cycle of today ("payday:25") or of a given day
+     * ("payday:25:2025-03-10"). Fiscal years start in January.
+     */
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        Self::parse(spec, 1)
+    }
+}
+
+/* The shortest spec parsed back into the same period. */
+impl fmt::Display for Period {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (start, end) = (self.start, self.end);
+
+        match self.whole_months() {
+            Some(12) if start.day() == 1 && start.month() == 1 => write!(f, "{}", start.year()),
+            Some(3) if start.day() == 1 && start.month() % 3 == 1 => {
+                write!(f, "{}-Q{}", start.year(), start.month().div_ceil(3))
+            }
+            Some(1) if start.day() == 1 => write!(f, "{}-{:02}", start.year(), start.month()),
+            _ if start == end => write!(f, "{}", start),
+            _ if start.weekday() == Weekday::Mon && end - start == Duration::days(6) => {
+                let week = start.iso_week();
+                write!(f, "{}-W{:02}", week.year(), week.week())
+            }
+            _ => write!(f, "{}..{}", start, end),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn period(spec: &str) -> Period {
+        spec.parse().unwrap()
+    }
+
+    #[test]
+    fn february_of_leap_years() {
+        assert_eq!(*Period::month(2, 2024).unwrap().end(), date(2024, 2, 29));
+        assert_eq!(*Period::month(2, 2023).unwrap().end(), date(2023, 2, 28));
+        assert_eq!(*Period::month(2, 2000).unwrap().end(), date(2000, 2, 29));
+        assert_eq!(*Period::month(2, 1900).unwrap().end(), date(1900, 2, 28));
+        assert_eq!(Period::month(2, 2024).unwrap().days(), 29);
+        assert_eq!(Period::year(2024).unwrap().days(), 366);
+        assert_eq!(Period::year(2025).unwrap().days(), 365);
+    }
+
+    #[test]
+    fn months_step_over_february() {
+        assert_eq!(period("2024-01").next().unwrap(), period("2024-02"));
+        assert_eq!(period("2024-02").next().unwrap(), period("2024-03"));
+        assert_eq!(period("2024-03").prev().unwrap(), period("2024-02"));
+        assert_eq!(period("2023-03").prev().unwrap(), period("2023-02"));
+        assert_eq!(period("2024-Q1").next().unwrap(), period("2024-Q2"));
+        assert_eq!(period("2024-02").year_before().unwrap(), period("2023-02"));
+    }
+
+    #[test]
+    fn steps_across_year_boundaries() {
+        assert_eq!(period("2024-12").next().unwrap(), period("2025-01"));
+        assert_eq!(period("2025-01").prev().unwrap(), period("2024-12"));
+        assert_eq!(period("2024-Q4").next().unwrap(), period("2025-Q1"));
+        assert_eq!(period("2024").next().unwrap(), period("2025"));
+        assert_eq!(period("2024-W52").next().unwrap(), period("2025-W01"));
+        assert_eq!(period("2026-W01").prev().unwrap(), period("2025-W52"));
+        assert_eq!(period("2024-12-31").next().unwrap(), period("2025-01-01"));
+    }
+
+    #[test]
+    fn pay_cycles_keep_the_payday() {
+        let cycle = Period::pay_cycle(25, date(2025, 1, 10)).unwrap();
+        assert_eq!(cycle, period("2024-12-25..2025-01-24"));
+        assert_eq!(cycle.next().unwrap(), period("2025-01-25..2025-02-24"));
+        assert_eq!(cycle.prev().unwrap(), period("2024-11-25..2024-12-24"));
+
+        /* The 31st falls on the last day of shorter months. */
+        let cycle = Period::pay_cycle(31, date(2024, 2, 15)).unwrap();
+        assert_eq!(cycle, period("2024-01-31..2024-02-28"));
+    }
+
+    #[test]
+    fn pay_cycles_on_the_31st_across_february() {
+        let cycle = Period::pay_cycle(31, date(2024, 2, 15)).unwrap();
+        let next = cycle.next().unwrap();
+        assert_eq!(next, period("2024-02-29..2024-03-30"));
+        assert_eq!(next.next().unwrap(), period("2024-03-31..2024-04-29"));
+        assert_eq!(
+            next.next().unwrap().next().unwrap(),
+            period("2024-04-30..2024-05-30")
+        );
+        assert_eq!(next.prev().unwrap(), cycle);
+        assert_eq!(cycle.prev().unwrap(), period("2023-12-31..2024-01-30"));
+        assert_eq!(
+            next.year_before().unwrap(),
+            period("2023-02-28..2023-03-30")
+        );
+    }
+
+    #[test]
+    fn other_lengths_step_by_days() {
+        let range = period("2025-03-10..2025-03-16");
+        assert_eq!(range.next().unwrap(), period("2025-03-17..2025-03-23"));
+        assert_eq!(range.prev().unwrap(), period("2025-03-03..2025-03-09"));
+    }
+
+    #[test]
+    fn iso_weeks_at_year_boundaries() {
+        assert_eq!(period("2025-W01"), period("2024-12-30..2025-01-05"));
+        assert_eq!(period("2020-W53"), period("2020-12-28..2021-01-03"));
+        assert!("2025-W53".parse::<Period>().is_err());
+    }
+
+    #[test]
+    fn contains_and_overlaps() {
+        let march = period("2025-03");
+        assert!(march.contains(date(2025, 3, 1)));
+        assert!(march.contains(date(2025, 3, 31)));
+        assert!(!march.contains(date(2025, 4, 1)));
+        assert!(!march.contains(date(2025, 2, 28)));
+
+        assert!(march.overlaps(&period("2025-03-31..2025-04-05")));
+        assert!(march.overlaps(&period("2025-Q1")));
+        assert!(!march.overlaps(&period("2025-04")));
+        assert!(!march.overlaps(&period("2025-02")));
+    }
+
+    #[test]
+    fn intersection_of_periods() {
+        let march = period("2025-03");
+        assert_eq!(
+            march.intersection(&period("2025-W14")),
+            Some(period("2025-03-31"))
+        );
+        assert_eq!(march.intersection(&period("2025")), Some(march.clone()));
+        assert_eq!(march.intersection(&period("2025-04")), None);
+    }
+
+    #[test]
+    fn split_into_days_weeks_and_months() {
+        let days = period("2024-02-27..2024-03-02").split(Step::Day);
+        assert_eq!(days.len(), 5);
+        assert_eq!(days[2], period("2024-02-29"));
+
+        /* 2025-03 starts on a Saturday and ends on a Monday. */
+        let weeks = period("2025-03").split(Step::Week);
+        assert_eq!(weeks.len(), 6);
+        assert_eq!(weeks[0], period("2025-03-01..2025-03-02"));
+        assert_eq!(weeks[1], period("2025-W10"));
+        assert_eq!(weeks[5], period("2025-03-31"));
+
+        let months = period("2024-12-15..2025-02-10").split(Step::Month);
+        assert_eq!(
+            months,
+            vec![
+                period("2024-12-15..2024-12-31"),
+                period("2025-01"),
+                period("2025-02-01..2025-02-10"),
+            ]
+        );
+        assert_eq!(period("2024").split(Step::Month).len(), 12);
+        assert_eq!(period("2024").split(Step::Day).len(), 366);
+    }
+
+    #[test]
+    fn display_parses_back() {
+        for spec in [
+            "2024",
+            "2024-Q4",
+            "2024-02",
+            "2024-02-29",
+            "2025-W01",
+            "2020-W53",
+            "2024-12-25..2025-01-24",
+        ] {
+            let parsed = period(spec);
+            assert_eq!(parsed.to_string(), spec);
+            assert_eq!(period(&parsed.to_string()), parsed);
+        }
+        assert_eq!(period("03-2025").to_string(), "2025-03");
+    }
+
+    #[test]
+    fn fiscal_years() {
+        let fiscal = Period::parse("FY2025", 4).unwrap();
+        assert_eq!(fiscal, period("2025-04-01..2026-03-31"));
+        assert_eq!(fiscal.next().unwrap(), Period::parse("FY2026", 4).unwrap());
+        assert_eq!(fiscal.to_string(), "2025-04-01..2026-03-31");
+    }
+}
@@ -0,0 +1,729 @@
+/*
+ * Filter language for listings, bulk deletes and exports:
+ *
+ *   [where <expr>] [since <date>] [until <date>] [order by <field> [asc|desc]] [limit <n>]
+ *
+ *   expr  := term ("or" term)*
+ *   term  := unary ("and" unary)*
+ *   unary := "not" unary | "(" expr ")" | <field> <op> <value>
+ *   field := category | amount | date | payee | note | tag
+ *   op    := = | != | > | >= | < | <= | ~ (contains) | !~ (does not contain)
+ *
+ * Text comparisons ignore case, values with spaces can be quoted.
+ */
+use crate::{database::expense::ExpenseRecord, error::BtrError};
+use chrono::NaiveDate;
+use std::cmp::Ordering;
+
+/* Words starting a query, used to find where a query begins in the command arguments. */
+pub const QUERY_KEYWORDS: [&str; 5] = ["where", "since", "until", "order", "limit"];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Field {
+    Category,
+    Amount,
+    Date,
+    Payee,
+    Note,
+    Tag,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    Contains,
+    NotContains,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Text(String),
+    Number(f32),
+    Date(NaiveDate),
+}
+
+#[derive(Debug, Clone)]
+enum Expr {
+    Compare(Field, Op, Value),
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+}
+
+#[derive(Debug, Clone)]
+pub struct FilterQuery {
+    filter: Option<Expr>,
+    since: Option<NaiveDate>,
+    until: Option<NaiveDate>,
+    order: Option<(Field, bool)>,
+    limit: Option<usize>,
+}
+
+impl FilterQuery {
+    pub fn parse(input: &str) -> Result<Self, BtrError> {
+        let tokens = tokenize(input)?;
+        Parser {
+            input,
+            tokens,
+            pos: 0,
+        }
+        .query()
+    }
+
+    pub fn since(&self) -> Option<NaiveDate> {
+        self.since
+    }
+
+    pub fn until(&self) -> Option<NaiveDate> {
+        self.until
+    }
+
+    pub fn matches(&self, record: &ExpenseRecord) -> bool {
+        let date = record.logged_on();
+
+        self.since.is_none_or(|since| date >= since)
+            && self.until.is_none_or(|until| date <= until)
+            && self.filter.as_ref().is_none_or(|expr| eval(expr, record))
+    }
+
+    /* Compares two records by the 'order by' clause. Equal when no ordering was requested. */
+    pub fn compare(&self, a: &ExpenseRecord, b: &ExpenseRecord) -> Ordering {
+        let Some((field, descending)) = self.order else {
+            return Ordering::Equal;
+        };
+
+        let ordering = match field {
+            Field::Amount => a.amount().total_cmp(&b.amount()),
+            Field::Date => a.logged_on().cmp(&b.logged_on()),
+            _ => text_of(field, a)
+                .to_lowercase()
+                .cmp(&text_of(field, b).to_lowercase()),
+        };
+
+        if descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    }
+
+    /* A condition or a limit leaves out some of the records in the date range. */
+    pub fn narrows(&self) -> bool {
+        self.filter.is_some() || self.limit.is_some()
+    }
+
+    pub fn limit(&self) -> Option<usize> {
+        self.limit
+    }
+
+    /* Filters, orders and limits the records, split expenses line by line. */
+    pub fn apply(&self, records: Vec<ExpenseRecord>) -> Vec<ExpenseRecord> {
+        let mut records: Vec<ExpenseRecord> = ExpenseRecord::itemize(&records)
+            .into_iter()
+            .filter(|r| self.matches(r))
+            .collect();
+
+        records.sort_by(|a, b| self.compare(a, b));
+        if let Some(limit) = self.limit {
+            records.truncate(limit);
+        }
+
+        records
+    }
+}
+
+fn text_of(field: Field, record: &ExpenseRecord) -> String {
+    match field {
+        Field::Category => record.category().to_string(),
+        Field::Payee => record.payee().unwrap_or_default().to_string(),
+        Field::Note => record.note().unwrap_or_default().to_string(),
+        Field::Tag => record.tags().join(","),
+        Field::Amount => record.amount().to_string(),
+        Field::Date => record.logged_on().to_string(),
+    }
+}
+
+fn eval(expr: &Expr, record: &ExpenseRecord) -> bool {
+    match expr {
+        Expr::Not(inner) => !eval(inner, record),
+        Expr::And(a, b) => eval(a, record) && eval(b, record),
+        Expr::Or(a, b) => eval(a, record) || eval(b, record),
+        Expr::Compare(field, op, value) => match (field, value) {
+            (Field::Amount, Value::Number(n)) => compare_ord(record.amount().total_cmp(n), *op),
+            (Field::Date, Value::Date(d)) => compare_ord(record.logged_on().cmp(d), *op),
+            /* A record matches a tag when any of its tags does, negations require none to. */
+            /* Categories of split lines behave the same way as tags. */
+            (Field::Category, Value::Text(text)) => {
+                let parts = record.parts();
+                match op {
+                    Op::Ne => !parts.iter().any(|(c, _)| compare_text(c, text, Op::Eq)),
+                    Op::NotContains => !parts
+                        .iter()
+                        .any(|(c, _)| compare_text(c, text, Op::Contains)),
+                    _ => parts.iter().any(|(c, _)| compare_text(c, text, *op)),
+                }
+            }
+            (Field::Tag, Value::Text(text)) => match op {
+                Op::Ne => !record.tags().iter().any(|t| compare_text(t, text, Op::Eq)),
+                Op::NotContains => !record
+                    .tags()
+                    .iter()
+                    .any(|t| compare_text(t, text, Op::Contains)),
+                _ => record.tags().iter().any(|t| compare_text(t, text, *op)),
+            },
+            (field, Value::Text(text)) => compare_text(&text_of(*field, record), text, *op),
+            /* Type mismatches are rejected by the parser. */
+            _ => false,
+        },
+    }
+}
+
+fn compare_ord(ordering: Ordering, op: Op) -> bool {
+    match op {
+        Op::Eq => ordering == Ordering::Equal,
+        Op::Ne => ordering != Ordering::Equal,
+        Op::Gt => ordering == Ordering::Greater,
+        Op::Ge => ordering != Ordering::Less,
+        Op::Lt => ordering == Ordering::Less,
+        Op::Le => ordering != Ordering::Greater,
+        Op::Contains | Op::NotContains => false,
+    }
+}
+
+fn compare_text(actual: &str, expected: &str, op: Op) -> bool {
+    let actual = actual.to_lowercase();
+    let expected = expected.to_lowercase();
+
+    match op {
+        Op::Contains => actual.contains(&expected),
+        Op::NotContains => !actual.contains(&expected),
+        _ => compare_ord(actual.cmp(&expected), op),
+    }
+}
+
+/* ---------------------- LEXER ---------------------- */
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Quoted(String),
+    Op(Op),
+    LParen,
+    RParen,
+    End,
+}
+
+/* Tokens with the character position they start at. */
+fn tokenize(input: &str) -> Result<Vec<(Token, usize)>, BtrError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+
+    while pos < chars.len() {
+        let start = pos;
+        let ch = chars[pos];
+
+        let token = match ch {
+            c if c.is_whitespace() => {
+                pos += 1;
+                continue;
+            }
+            '(' => {
+                pos += 1;
+                Token::LParen
+            }
+            ')' => {
+                pos += 1;
+                Token::RParen
+            }
+            '"' | '\'' => {
+                pos += 1;
+                let text_start = pos;
+                while pos < chars.len() && chars[pos] != ch {
+                    pos += 1;
+                }
+                if pos == chars.len() {
+                    return Err(query_error(input, start, "unterminated string"));
+                }
+                pos += 1;
+                Token::Quoted(chars[text_start..pos - 1].iter().collect())
+            }
+            '=' | '!' | '<' | '>' | '~' => {
+                let next = chars.get(pos + 1).copied();
+                let (op, len) = match (ch, next) {
+                    ('!', Some('=')) => (Op::Ne, 2),
+                    ('!', Some('~')) => (Op::NotContains, 2),
+                    ('>', Some('=')) => (Op::Ge, 2),
+                    ('<', Some('=')) => (Op::Le, 2),
+                    ('=', Some('=')) => (Op::Eq, 2),
+                    ('=', _) => (Op::Eq, 1),
+                    ('>', _) => (Op::Gt, 1),
+                    ('<', _) => (Op::Lt, 1),
+                    ('~', _) => (Op::Contains, 1),
+                    _ => return Err(query_error(input, start, "unknown operator")),
+                };
+                pos += len;
+                Token::Op(op)
+            }
+            _ => {
+                while pos < chars.len()
+                    && !chars[pos].is_whitespace()
+                    && !"()=!<>~\"'".contains(chars[pos])
+                {
+                    pos += 1;
+                }
+                Token::Word(chars[start..pos].iter().collect())
+            }
+        };
+
+        tokens.push((token, start));
+    }
+
+    tokens.push((Token::End, chars.len()));
+    Ok(tokens)
+}
+
+fn query_error(input: &str, position: usize, message: &str) -> BtrError {
+    BtrError::InvalidQuery {
+        query: input.to_string(),
+        position,
+        message: message.to_string(),
+    }
+}
+
+/* ---------------------- PARSER ---------------------- */
+struct Parser<'a> {
+    input: &'a str,
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.pos].0
+    }
+
+    fn position(&self) -> usize {
+        self.tokens[self.pos].1
+    }
+
+    fn advance(&mut self) -> Token {
+        let token = self.tokens[self.pos].0.clone();
+        if token != Token::End {
+            self.pos += 1;
+        }
+        token
+    }
+
+    fn error(&self, message: &str) -> BtrError {
+        query_error(self.input, self.position(), message)
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Token::Word(w) if w.eq_ignore_ascii_case(keyword))
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<(), BtrError> {
+        if self.is_keyword(keyword) {
+            self.advance();
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{}'", keyword)))
+        }
+    }
+
+    fn query(mut self) -> Result<FilterQuery, BtrError> {
+        let mut query = FilterQuery {
+            filter: None,
+            since: None,
+            until: None,
+            order: None,
+            limit: None,
+        };
+
+        while *self.peek() != Token::End {
+            let clause_pos = self.position();
+            let Token::Word(word) = self.advance() else {
+                return Err(query_error(
+                    self.input,
+                    clause_pos,
+                    "expected 'where', 'since', 'until', 'order by' or 'limit'",
+                ));
+            };
+
+            let duplicate = match word.to_ascii_lowercase().as_str() {
+                "where" => query.filter.replace(self.expr()?).is_some(),
+                "since" => query.since.replace(self.date()?).is_some(),
+                "until" => query.until.replace(self.date()?).is_some(),
+                "order" => {
+                    self.expect_keyword("by")?;
+                    let field = self.field()?;
+                    let descending = if self.is_keyword("desc") {
+                        self.advance();
+                        true
+                    } else {
+                        if self.is_keyword("asc") {
+                            self.advance();
+                        }
+                        false
+                    };
+                    query.order.replace((field, descending)).is_some()
+                }
+                "limit" => {
+                    let limit_pos = self.position();
+                    let limit = match self.advance() {
+                        Token::Word(w) => w.parse::<usize>().ok(),
+                        _ => None,
+                    }
+                    .ok_or_else(|| query_error(self.input, limit_pos, "expected a whole number"))?;
+                    query.limit.replace(limit).is_some()
+                }
+                _ => {
+                    return Err(query_error(
+                        self.input,
+                        clause_pos,
+                        &format!(
+                            "unexpected '{}', expected 'where', 'since', 'until', 'order by' or 'limit'",
+                            word
+                        ),
+                    ));
+                }
+            };
+
+            if duplicate {
+                return Err(query_error(
+                    self.input,
+                    clause_pos,
+                    &format!("'{}' used more than once", word),
+                ));
+            }
+        }
+
+        Ok(query)
+    }
+
+    fn expr(&mut self) -> Result<Expr, BtrError> {
+        let mut left = self.term()?;
+        while self.is_keyword("or") {
+            self.advance();
+            left = Expr::Or(Box::new(left), Box::new(self.term()?));
+        }
+        Ok(left)
+    }
+
+    fn term(&mut self) -> Result<Expr, BtrError> {
+        let mut left = self.unary()?;
+        while self.is_keyword("and") {
+            self.advance();
+            left = Expr::And(Box::new(left), Box::new(self.unary()?));
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Expr, BtrError> {
+        if self.is_keyword("not") {
+            self.advance();
+            return Ok(Expr::Not(Box::new(self.unary()?)));
+        }
+
+        if *self.peek() == Token::LParen {
+            self.advance();
+            let inner = self.expr()?;
+            if *self.peek() != Token::RParen {
+                return Err(self.error("expected ')'"));
+            }
+            self.advance();
+            return Ok(inner);
+        }
+
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Result<Expr, BtrError> {
+        let field = self.field()?;
+
+        let op_pos = self.position();
+        let Token::Op(op) = self.advance() else {
+            return Err(query_error(
+                self.input,
+                op_pos,
+                "expected an operator: =, !=, >, >=, <, <=, ~ or !~",
+            ));
+        };
+
+        let value_pos = self.position();
+        let raw = match self.advance() {
+            Token::Word(w) => w,
+            Token::Quoted(q) => q,
+            _ => return Err(query_error(self.input, value_pos, "expected a value")),
+        };
+
+        let value = match field {
+            Field::Amount => Value::Number(
+                raw.parse()
+                    .map_err(|_| query_error(self.input, value_pos, "expected a number"))?,
+            ),
+            Field::Date => Value::Date(parse_date(&raw).ok_or_else(|| {
+                query_error(self.input, value_pos, "expected a date as YYYY-MM-DD")
+            })?),
+            _ => Value::Text(raw),
+        };
+
+        let text_op = matches!(op, Op::Contains | Op::NotContains);
+        let text_field = matches!(value, Value::Text(_));
+        if text_op && !text_field {
+            return Err(query_error(
+                self.input,
+                op_pos,
+                "'~' and '!~' can only be used with text fields",
+            ));
+        }
+
+        Ok(Expr::Compare(field, op, value))
+    }
+
+    fn field(&mut self) -> Result<Field, BtrError> {
+        let field_pos = self.position();
+        let field = match self.advance() {
+            Token::Word(w) => match w.to_ascii_lowercase().as_str() {
+                "category" => Some(Field::Category),
+                "amount" => Some(Field::Amount),
+                "date" => Some(Field::Date),
+                "payee" => Some(Field::Payee),
+                "note" => Some(Field::Note),
+                "tag" | "tags" => Some(Field::Tag),
+                _ => None,
+            },
+            _ => None,
+        };
+
+        field.ok_or_else(|| {
+            query_error(
+                self.input,
+                field_pos,
+                "expected a field: category, amount, date, payee, note or tag",
+            )
+        })
+    }
+
+    fn date(&mut self) -> Result<NaiveDate, BtrError> {
+        let date_pos = self.position();
+        match self.advance() {
+            Token::Word(w) | Token::Quoted(w) => parse_date(&w),
+            _ => None,
+        }
+        .ok_or_else(|| query_error(self.input, date_pos, "expected a date as YYYY-MM-DD"))
+    }
+}
+
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::expense::SplitLine;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn record(category: &str, amount: f32, day: u32) -> ExpenseRecord {
+        ExpenseRecord::new(category.to_string(), amount, date(2025, 3, day))
+    }
+
+    fn matches(query: &str, record: &ExpenseRecord) -> bool {
+        FilterQuery::parse(query).unwrap().matches(record)
+    }
+
+    /* Position and message of the error the query is rejected with. */
+    fn error(query: &str) -> (usize, String) {
+        match FilterQuery::parse(query) {
+            Err(BtrError::InvalidQuery {
+                position, message, ..
+            }) => (position, message),
+            other => panic!("'{}' was not rejected: {:?}", query, other),
+        }
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        let rent = record("Rent", 50.0, 1);
+        let groceries = record("Groceries", 50.0, 1);
+
+        let query = "where category = Rent or category = Groceries and amount > 100";
+        assert!(matches(query, &rent));
+        assert!(!matches(query, &groceries));
+
+        let query = "where (category = Rent or category = Groceries) and amount > 100";
+        assert!(!matches(query, &rent));
+        assert!(!matches(query, &groceries));
+        assert!(matches(query, &record("Rent", 150.0, 1)));
+    }
+
+    #[test]
+    fn not_applies_to_the_next_condition() {
+        let rent = record("Rent", 50.0, 1);
+
+        assert!(!matches("where not category = rent", &rent));
+        assert!(matches("where not category = rent or amount = 50", &rent));
+        assert!(!matches(
+            "where not (category = rent or amount = 50)",
+            &rent
+        ));
+        assert!(matches("where not not category = rent", &rent));
+    }
+
+    #[test]
+    fn quoted_values_keep_spaces() {
+        let laptop =
+            record("Electronics", 4999.0, 1).with_payee(Some(String::from("Media Expert")));
+
+        assert!(matches("where payee = \"media expert\"", &laptop));
+        assert!(matches("where payee = 'Media Expert'", &laptop));
+        assert!(matches("where payee ~ \"a e\"", &laptop));
+        assert!(!matches("where payee = media", &laptop));
+        assert!(matches("where payee !~ 'lidl'", &laptop));
+    }
+
+    #[test]
+    fn comparisons_follow_the_field_type() {
+        let ten = record("Groceries", 10.0, 9);
+
+        /* As text "10" would sort before "9". */
+        assert!(matches("where amount > 9", &ten));
+        assert!(matches("where amount >= 10 and amount <= 10.0", &ten));
+        assert!(matches("where date < 2025-03-10", &ten));
+        assert!(matches("where date >= 2025-03-09", &ten));
+        assert!(!matches("where date != 2025-03-09", &ten));
+        assert!(matches("where category = GROCERIES", &ten));
+        assert!(matches("where category < Rent", &ten));
+    }
+
+    #[test]
+    fn types_are_checked_when_parsing() {
+        assert_eq!(
+            error("where amount > abc"),
+            (15, String::from("expected a number"))
+        );
+        assert_eq!(
+            error("where date = 2025-13-01"),
+            (13, String::from("expected a date as YYYY-MM-DD"))
+        );
+        assert_eq!(
+            error("where amount ~ 5"),
+            (
+                13,
+                String::from("'~' and '!~' can only be used with text fields")
+            )
+        );
+    }
+
+    #[test]
+    fn tags_and_split_lines_match_any() {
+        let tagged = record("Groceries", 10.0, 1)
+            .with_tags(vec![String::from("weekly"), String::from("family")]);
+        assert!(matches("where tag = family", &tagged));
+        assert!(!matches("where tag != family", &tagged));
+        assert!(matches("where tag != work", &tagged));
+        assert!(!matches("where tag !~ fam", &tagged));
+
+        let receipt = ExpenseRecord::split(
+            vec![
+                SplitLine {
+                    category: String::from("Groceries"),
+                    amount: 30.0,
+                    note: None,
+                },
+                SplitLine {
+                    category: String::from("Household"),
+                    amount: 20.0,
+                    note: None,
+                },
+            ],
+            date(2025, 3, 1),
+        )
+        .unwrap();
+        assert!(matches("where category = household", &receipt));
+        assert!(!matches("where category != household", &receipt));
+
+        /* Applied to a list the lines are filtered one by one. */
+        let lines = FilterQuery::parse("where category = household")
+            .unwrap()
+            .apply(vec![receipt]);
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].amount(), 20.0);
+    }
+
+    #[test]
+    fn date_range_order_and_limit() {
+        let records = vec![
+            record("Groceries", 30.0, 2),
+            record("Transport", 5.0, 12),
+            record("Groceries", 80.0, 20),
+            record("Rent", 2500.0, 28),
+        ];
+
+        let query = FilterQuery::parse("since 2025-03-10 until 2025-03-25").unwrap();
+        assert_eq!(query.since(), Some(date(2025, 3, 10)));
+        assert_eq!(query.until(), Some(date(2025, 3, 25)));
+        assert_eq!(query.apply(records.clone()).len(), 2);
+
+        let query = FilterQuery::parse("order by amount desc limit 2").unwrap();
+        let amounts: Vec<f32> = query
+            .apply(records.clone())
+            .iter()
+            .map(|r| r.amount())
+            .collect();
+        assert_eq!(amounts, vec![2500.0, 80.0]);
+        assert_eq!(query.limit(), Some(2));
+
+        let query = FilterQuery::parse("ORDER BY category").unwrap();
+        let categories: Vec<String> = query
+            .apply(records)
+            .iter()
+            .map(|r| r.category().to_string())
+            .collect();
+        assert_eq!(
+            categories,
+            vec!["Groceries", "Groceries", "Rent", "Transport"]
+        );
+    }
+
+    #[test]
+    fn narrows_only_with_a_condition_or_a_limit() {
+        let narrows = |query: &str| FilterQuery::parse(query).unwrap().narrows();
+
+        assert!(!narrows(""));
+        assert!(!narrows("since 2025-03-01 until 2025-03-31"));
+        assert!(!narrows("order by date"));
+        assert!(narrows("where amount > 0"));
+        assert!(narrows("limit 10"));
+    }
+
+    #[test]
+    fn errors_point_at_the_token() {
+        assert_eq!(error("where payee = \"Lidl").0, 14);
+        assert_eq!(error("where (amount > 5").0, 17);
+        assert_eq!(error("where amount 5").0, 13);
+        assert_eq!(error("where price > 5").0, 6);
+        assert_eq!(error("where amount > 5 limit x").0, 23);
+        assert_eq!(error("where amount ! 5").0, 13);
+        assert_eq!(error("order amount").0, 6);
+        assert_eq!(error("since 2025-03-01 since 2025-03-02").0, 17);
+        assert_eq!(error("where amount > 5 sort by date").0, 17);
+        assert_eq!(error("where amount > ").0, 15);
+    }
+
+    #[test]
+    fn positions_count_characters() {
+        /* 'Ż' takes two bytes, the position of 'amout' is still 26. */
+        assert_eq!(error("where payee = \"Żabka\" and amout > 5").0, 26);
+        assert_eq!(error("where note = źdźbło and (amount > 5").0, 35);
+    }
+}
@@ -0,0 +1,6 @@
+mod lang;
+mod records;
+
+/* Re-exports */
+pub use lang::{FilterQuery, QUERY_KEYWORDS};
+pub use records::{RecordQuery, records_span, same_income, same_record, same_transfer};
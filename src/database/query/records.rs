@@ -0,0 +1,290 @@
+use crate::database::{
+    expense::{Debt, ExpenseRecord, ExpenseSheet, IncomeRecord, Settlement, TransferRecord},
+    periods::Period,
+};
+use chrono::NaiveDate;
+use std::{collections::HashMap, hash::Hash};
+
+/* Filters applied to records loaded from every sheet. Empty filters match everything. */
+#[derive(Debug, Default, Clone)]
+pub struct RecordQuery {
+    range: Option<Period>,
+    categories: Vec<String>,
+    tags: Vec<String>,
+    payees: Vec<String>,
+    min_amount: Option<f32>,
+    max_amount: Option<f32>,
+}
+
+/* Identifies the same record saved in more than one sheet. */
+type RecordKey = (NaiveDate, String, u32, Option<String>, Option<String>);
+type IncomeKey = (NaiveDate, String, u32, Option<String>);
+type TransferKey = (NaiveDate, String, String, u32, Option<String>);
+type SettlementKey = (NaiveDate, String, String, u32);
+
+impl RecordQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn range(mut self, period: Period) -> Self {
+        self.range = Some(period);
+        self
+    }
+
+    pub fn category(mut self, category: &str) -> Self {
+        self.categories.push(category.to_string());
+        self
+    }
+
+    pub fn tag(mut self, tag: &str) -> Self {
+        self.tags.push(tag.to_string());
+        self
+    }
+
+    pub fn payee(mut self, payee: &str) -> Self {
+        self.payees.push(payee.to_string());
+        self
+    }
+
+    pub fn min_amount(mut self, amount: f32) -> Self {
+        self.min_amount = Some(amount);
+        self
+    }
+
+    pub fn max_amount(mut self, amount: f32) -> Self {
+        self.max_amount = Some(amount);
+        self
+    }
+
+    pub fn get_range(&self) -> Option<&Period> {
+        self.range.as_ref()
+    }
+
+    pub fn matches(&self, record: &ExpenseRecord) -> bool {
+        self.in_range(record.logged_on())
+            && (self.categories.is_empty()
+                || self.categories.iter().any(|c| {
+                    record
+                        .parts()
+                        .iter()
+                        .any(|(category, _)| c.eq_ignore_ascii_case(category))
+                }))
+            && (self.tags.is_empty()
+                || self
+                    .tags
+                    .iter()
+                    .any(|t| record.tags().iter().any(|rt| rt.eq_ignore_ascii_case(t))))
+            && (self.payees.is_empty()
+                || record
+                    .payee()
+                    .is_some_and(|rp| self.payees.iter().any(|p| p.eq_ignore_ascii_case(rp))))
+            && self.min_amount.is_none_or(|min| record.amount() >= min)
+            && self.max_amount.is_none_or(|max| record.amount() <= max)
+    }
+
+    /*
+     * Single, date ordered stream of the matching records from all given sheets. Filtering
+     * by category keeps only the matching lines of split expenses.
+     */
+    pub fn run(&self, sheets: &[ExpenseSheet]) -> Vec<ExpenseRecord> {
+        let mut records: Vec<ExpenseRecord> = overlap_groups(sheets)
+            .into_iter()
+            .flat_map(|group| dedup_group(&group, ExpenseSheet::expenses, record_key))
+            .flat_map(|record| {
+                if self.categories.is_empty() {
+                    vec![record]
+                } else {
+                    record.itemized()
+                }
+            })
+            .filter(|record| self.matches(record))
+            .collect();
+
+        records.sort_by_key(|record| record.logged_on());
+        records
+    }
+
+    /* Filters other than the range narrow down expenses, not what was earned. */
+    pub fn is_range_only(&self) -> bool {
+        self.categories.is_empty()
+            && self.tags.is_empty()
+            && self.payees.is_empty()
+            && self.min_amount.is_none()
+            && self.max_amount.is_none()
+    }
+
+    /* Date ordered income from all given sheets within the range. */
+    pub fn run_income(&self, sheets: &[ExpenseSheet]) -> Vec<IncomeRecord> {
+        self.run_entries(
+            sheets,
+            ExpenseSheet::income,
+            income_key,
+            IncomeRecord::logged_on,
+        )
+    }
+
+    /* Date ordered transfers from all given sheets within the range. */
+    pub fn run_transfers(&self, sheets: &[ExpenseSheet]) -> Vec<TransferRecord> {
+        self.run_entries(
+            sheets,
+            ExpenseSheet::transfers,
+            transfer_key,
+            TransferRecord::logged_on,
+        )
+    }
+
+    /* Date ordered settlements from all given sheets within the range. */
+    pub fn run_settlements(&self, sheets: &[ExpenseSheet]) -> Vec<Settlement> {
+        self.run_entries(sheets, ExpenseSheet::settlements, settlement_key, |s| {
+            s.logged_on
+        })
+    }
+
+    /* Date ordered debts taken in any of the given sheets within the range. */
+    pub fn run_debts(&self, sheets: &[ExpenseSheet]) -> Vec<Debt> {
+        self.run_entries(sheets, ExpenseSheet::debts, |d| d.id, |d| d.logged_on)
+    }
+
+    /* Entries other than expenses are only narrowed down by the range. */
+    fn run_entries<T: Clone, K: Hash + Eq + Clone>(
+        &self,
+        sheets: &[ExpenseSheet],
+        items: fn(&ExpenseSheet) -> &[T],
+        key_of: fn(&T) -> K,
+        date_of: fn(&T) -> NaiveDate,
+    ) -> Vec<T> {
+        let mut entries: Vec<T> = overlap_groups(sheets)
+            .into_iter()
+            .flat_map(|group| dedup_group(&group, items, key_of))
+            .filter(|entry| self.in_range(date_of(entry)))
+            .collect();
+
+        entries.sort_by_key(date_of);
+        entries
+    }
+
+    fn in_range(&self, date: NaiveDate) -> bool {
+        self.range.as_ref().is_none_or(|p| p.contains(date))
+    }
+}
+
+/* Groups sheets whose periods overlap, directly or through another sheet. */
+fn overlap_groups(sheets: &[ExpenseSheet]) -> Vec<Vec<&ExpenseSheet>> {
+    let mut sorted: Vec<&ExpenseSheet> = sheets.iter().collect();
+    sorted.sort_by_key(|sheet| *sheet.period.start());
+
+    let mut groups: Vec<Vec<&ExpenseSheet>> = Vec::new();
+    /* Days from the start of the current group to the latest end of its sheets. */
+    let mut group_span: Option<Period> = None;
+
+    for sheet in sorted {
+        match (groups.last_mut(), &group_span) {
+            (Some(group), Some(span)) if span.overlaps(&sheet.period) => {
+                group.push(sheet);
+                group_span = Period::new(*span.start(), *span.end().max(sheet.period.end())).ok();
+            }
+            _ => {
+                groups.push(vec![sheet]);
+                group_span = Some(sheet.period.clone());
+            }
+        }
+    }
+
+    groups
+}
+
+/*
+ * Records present in several overlapping sheets are kept once. A record repeated within
+ * one sheet is a real repetition, so every key is emitted as many times as the sheet
+ * containing it most often.
+ */
+fn dedup_group<T: Clone, K: Hash + Eq + Clone>(
+    group: &[&ExpenseSheet],
+    items: fn(&ExpenseSheet) -> &[T],
+    key_of: fn(&T) -> K,
+) -> Vec<T> {
+    if let [sheet] = group {
+        return items(sheet).to_vec();
+    }
+
+    let mut emitted: HashMap<K, usize> = HashMap::new();
+    let mut records = Vec::new();
+
+    for sheet in group {
+        let mut seen_in_sheet: HashMap<K, usize> = HashMap::new();
+
+        for record in items(sheet) {
+            let key = key_of(record);
+            let seen = seen_in_sheet.entry(key.clone()).or_default();
+            *seen += 1;
+
+            let already = emitted.entry(key).or_default();
+            if *seen > *already {
+                *already += 1;
+                records.push(record.clone());
+            }
+        }
+    }
+
+    records
+}
+
+fn record_key(record: &ExpenseRecord) -> RecordKey {
+    (
+        record.logged_on(),
+        record.category().to_string(),
+        record.amount().to_bits(),
+        record.payee().map(str::to_string),
+        record.note().map(str::to_string),
+    )
+}
+
+fn income_key(income: &IncomeRecord) -> IncomeKey {
+    (
+        income.logged_on(),
+        income.source().to_string(),
+        income.amount().to_bits(),
+        income.note().map(str::to_string),
+    )
+}
+
+fn transfer_key(transfer: &TransferRecord) -> TransferKey {
+    (
+        transfer.logged_on(),
+        transfer.from().to_string(),
+        transfer.to().to_string(),
+        transfer.amount().to_bits(),
+        transfer.note().map(str::to_string),
+    )
+}
+
+fn settlement_key(settlement: &Settlement) -> SettlementKey {
+    (
+        settlement.logged_on,
+        settlement.from.clone(),
+        settlement.to.clone(),
+        settlement.amount.to_bits(),
+    )
+}
+
+/* Whether both are copies of the same expense, saved in overlapping sheets. */
+pub fn same_record(a: &ExpenseRecord, b: &ExpenseRecord) -> bool {
+    record_key(a) == record_key(b)
+}
+
+pub fn same_income(a: &IncomeRecord, b: &IncomeRecord) -> bool {
+    income_key(a) == income_key(b)
+}
+
+pub fn same_transfer(a: &TransferRecord, b: &TransferRecord) -> bool {
+    transfer_key(a) == transfer_key(b)
+}
+
+/* Period spanned by the records, used when a query has no explicit range. */
+pub fn records_span(records: &[ExpenseRecord]) -> Option<Period> {
+    let first = records.iter().map(|r| r.logged_on()).min()?;
+    let last = records.iter().map(|r| r.logged_on()).max()?;
+
+    Period::new(first, last).ok()
+}
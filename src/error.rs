@@ -6,6 +6,11 @@ pub enum BtrError {
     Io(io::Error),
     InvalidData(Option<String>),
     InvalidPeriod(String),
+    InvalidQuery {
+        query: String,
+        position: usize,
+        message: String,
+    },
     ActiveSheetNotSelected,
 }
 
@@ -14,6 +19,7 @@ pub enum BtrErrorKind {
     Io(io::ErrorKind),
     InvalidData,
     InvalidPeriod,
+    InvalidQuery,
     ActiveSheetNotSelected,
 }
 
@@ -23,6 +29,7 @@ impl BtrError {
             BtrError::Io(e) => BtrErrorKind::Io(e.kind()),
             BtrError::InvalidData(_) => BtrErrorKind::InvalidData,
             BtrError::InvalidPeriod(_) => BtrErrorKind::InvalidPeriod,
+            BtrError::InvalidQuery { .. } => BtrErrorKind::InvalidQuery,
             BtrError::ActiveSheetNotSelected => BtrErrorKind::ActiveSheetNotSelected,
         }
     }
@@ -35,6 +42,17 @@ impl fmt::Display for BtrError {
             BtrError::InvalidData(None) => write!(f, "Invalid data"),
             BtrError::InvalidData(Some(msg)) => write!(f, "Invalid data: {}", msg),
             BtrError::InvalidPeriod(msg) => write!(f, "Invalid period: {}", msg),
+            BtrError::InvalidQuery {
+                query,
+                position,
+                message,
+            } => write!(
+                f,
+                "Invalid query: {}\n!     {}\n!     {}^",
+                message,
+                query,
+                " ".repeat(*position)
+            ),
             BtrError::ActiveSheetNotSelected => write!(f, "No active sheet selected"),
         }
     }
@@ -1,6 +1,7 @@
 mod console;
 mod database;
 mod error;
+mod report;
 mod utils;
 
 use crate::console::cli::TrackerCli;
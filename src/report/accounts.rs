@@ -0,0 +1,105 @@
+use crate::database::{
+    config::{Account, account::find_account},
+    expense::{ExpenseRecord, IncomeRecord, TransferRecord},
+    periods::Period,
+};
+use chrono::NaiveDate;
+
+/* Name under which records without an account are shown when there is no default one. */
+pub const UNASSIGNED: &str = "(unassigned)";
+
+#[derive(Debug, Clone)]
+pub struct AccountMovement {
+    pub name: String,
+    /* Balance at the start of the period. */
+    pub opening: f32,
+    /* Income and transfers received within the period. */
+    pub inflow: f32,
+    /* Expenses and transfers sent within the period. */
+    pub outflow: f32,
+}
+
+impl AccountMovement {
+    pub fn closing(&self) -> f32 {
+        self.opening + self.inflow - self.outflow
+    }
+}
+
+/*
+ * Balance and movement of every account in the period. The records are expected to cover
+ * the whole history, the ones after the period are ignored.
+ */
+pub fn account_movements(
+    accounts: &[Account],
+    period: &Period,
+    expenses: &[ExpenseRecord],
+    income: &[IncomeRecord],
+    transfers: &[TransferRecord],
+) -> Vec<AccountMovement> {
+    let mut movements: Vec<AccountMovement> = accounts
+        .iter()
+        .map(|account| AccountMovement {
+            name: account.name.clone(),
+            opening: account.opening_balance,
+            inflow: 0.0,
+            outflow: 0.0,
+        })
+        .collect();
+
+    let mut book = |account: Option<&str>, date: NaiveDate, amount: f32| {
+        if date > *period.end() {
+            return;
+        }
+
+        let name = resolve(accounts, account);
+        let idx = match movements.iter().position(|m| m.name == name) {
+            Some(idx) => idx,
+            None => {
+                movements.push(AccountMovement {
+                    name,
+                    opening: 0.0,
+                    inflow: 0.0,
+                    outflow: 0.0,
+                });
+                movements.len() - 1
+            }
+        };
+
+        let movement = &mut movements[idx];
+        if date < *period.start() {
+            movement.opening += amount;
+        } else if amount >= 0.0 {
+            movement.inflow += amount;
+        } else {
+            movement.outflow -= amount;
+        }
+    };
+
+    for record in expenses {
+        book(record.account(), record.logged_on(), -record.amount());
+    }
+    for record in income {
+        book(record.account(), record.logged_on(), record.amount());
+    }
+    for transfer in transfers {
+        book(
+            Some(transfer.from()),
+            transfer.logged_on(),
+            -transfer.amount(),
+        );
+        book(Some(transfer.to()), transfer.logged_on(), transfer.amount());
+    }
+
+    movements
+}
+
+/* Configured name of the account, the default one when none is given. */
+pub fn resolve(accounts: &[Account], account: Option<&str>) -> String {
+    match account {
+        Some(name) => find_account(accounts, name).map_or(name.to_string(), |a| a.name.clone()),
+        None => accounts
+            .iter()
+            .find(|a| a.default)
+            .map_or(UNASSIGNED.to_string(), |a| a.name.clone()),
+    }
+}
@@ -0,0 +1,213 @@
+use crate::{
+    database::{
+        expense::{AnomalyThresholds, ExpenseCategory, ExpenseRecord},
+        periods::Period,
+    },
+    report::{stats::median, summary::covered_months},
+};
+use chrono::{Datelike, Months};
+use std::collections::BTreeMap;
+
+/* Category records needed before its median is trusted. */
+const MIN_HISTORY: usize = 5;
+/* Calendar months before a period its total is compared against. */
+const JUMP_MONTHS: u32 = 3;
+
+#[derive(Debug, Clone)]
+pub enum AnomalyKind {
+    AboveMedian { median: f32 },
+    NewPayee { payee: String, median: f32 },
+    MonthlyJump { average: f32, total: f32 },
+}
+
+#[derive(Debug, Clone)]
+pub struct Anomaly {
+    pub category: String,
+    /* The record the anomaly is about, None for category wide ones. */
+    pub record: Option<ExpenseRecord>,
+    pub kind: AnomalyKind,
+}
+
+impl Anomaly {
+    pub fn describe(&self) -> String {
+        let amount = self.record.as_ref().map_or(0.0, |r| r.amount());
+
+        match &self.kind {
+            AnomalyKind::AboveMedian { median } => format!(
+                "{:.2} PLN on {} is {:.1}x the usual {:.2} PLN",
+                amount,
+                self.category,
+                amount / median,
+                median
+            ),
+            AnomalyKind::NewPayee { payee, median } => format!(
+                "first expense at '{}', {:.2} PLN is {:.1}x the usual {} expense",
+                payee,
+                amount,
+                amount / median,
+                self.category
+            ),
+            AnomalyKind::MonthlyJump { average, total } => format!(
+                "{} total {:.2} PLN is {:.1}x the average of {:.2} PLN in previous months",
+                self.category,
+                total,
+                total / average,
+                average
+            ),
+        }
+    }
+}
+
+fn thresholds_for(categories: &[ExpenseCategory], name: &str) -> AnomalyThresholds {
+    categories
+        .iter()
+        .find(|c| c.name == name)
+        .map(|c| c.anomaly.clone())
+        .unwrap_or_default()
+}
+
+/* Compares a single record with the earlier records of its category. */
+pub fn check_record(
+    record: &ExpenseRecord,
+    history: &[ExpenseRecord],
+    categories: &[ExpenseCategory],
+) -> Vec<Anomaly> {
+    /* Every line of a split expense is judged within its own category. */
+    if !record.lines().is_empty() {
+        return record
+            .itemized()
+            .iter()
+            .flat_map(|line| check_record(line, history, categories))
+            .collect();
+    }
+
+    let thresholds = thresholds_for(categories, record.category());
+    if record.is_refund() || record.is_debt() || record.amount() < thresholds.min_amount {
+        return Vec::new();
+    }
+
+    let mut amounts: Vec<f32> = history
+        .iter()
+        .filter(|r| !r.is_refund() && !r.is_debt())
+        .flat_map(|r| r.parts())
+        .filter(|(category, _)| *category == record.category())
+        .map(|(_, amount)| amount)
+        .collect();
+    if amounts.len() < MIN_HISTORY {
+        return Vec::new();
+    }
+
+    amounts.sort_by(f32::total_cmp);
+    let median = median(&amounts);
+    if median <= 0.0 {
+        return Vec::new();
+    }
+
+    let mut anomalies = Vec::new();
+    let anomaly = |kind| Anomaly {
+        category: record.category().to_string(),
+        record: Some(record.clone()),
+        kind,
+    };
+
+    if record.amount() > median * thresholds.median_ratio {
+        anomalies.push(anomaly(AnomalyKind::AboveMedian { median }));
+    }
+
+    if let Some(payee) = record.payee() {
+        let known = history
+            .iter()
+            .any(|r| r.payee().is_some_and(|p| p.eq_ignore_ascii_case(payee)));
+        if !known && record.amount() > median * thresholds.new_payee_ratio {
+            anomalies.push(anomaly(AnomalyKind::NewPayee {
+                payee: payee.to_string(),
+                median,
+            }));
+        }
+    }
+
+    anomalies
+}
+
+/*
+ * Categories whose total in the period jumped compared with the calendar months before it,
+ * a month without records counting as nothing spent. Needs records reaching back to the
+ * first of these months.
+ */
+pub fn check_monthly_jumps(
+    period: &Period,
+    records: &[ExpenseRecord],
+    history: &[ExpenseRecord],
+    categories: &[ExpenseCategory],
+) -> Vec<Anomaly> {
+    let start = *period.start();
+    let months: Vec<Period> = (1..=JUMP_MONTHS)
+        .filter_map(|n| start.checked_sub_months(Months::new(n)))
+        .filter_map(|date| Period::month(date.month(), date.year()).ok())
+        .collect();
+    let tracked = months.last().is_some_and(|first| {
+        history
+            .iter()
+            .any(|r| r.logged_on() <= *first.end() && !r.is_debt())
+    });
+    if months.len() < JUMP_MONTHS as usize || !tracked {
+        return Vec::new();
+    }
+
+    let mut totals: BTreeMap<&str, f32> = BTreeMap::new();
+    for (category, amount) in records
+        .iter()
+        .filter(|r| !r.is_debt())
+        .flat_map(|r| r.parts())
+    {
+        *totals.entry(category).or_default() += amount;
+    }
+
+    totals
+        .into_iter()
+        .filter_map(|(category, total)| {
+            let previous: f32 = history
+                .iter()
+                .filter(|r| {
+                    !r.is_debt()
+                        && r.logged_on() < start
+                        && months.iter().any(|m| m.contains(r.logged_on()))
+                })
+                .flat_map(|r| r.parts())
+                .filter(|(c, _)| *c == category)
+                .map(|(_, amount)| amount)
+                .sum();
+
+            /* Periods other than a month are compared with as many months as they cover. */
+            let average = previous / JUMP_MONTHS as f32 * covered_months(period);
+
+            let thresholds = thresholds_for(categories, category);
+            (average > 0.0 && total > average * thresholds.monthly_jump).then(|| Anomaly {
+                category: category.to_string(),
+                record: None,
+                kind: AnomalyKind::MonthlyJump { average, total },
+            })
+        })
+        .collect()
+}
+
+/* All anomalies in the records of a period, each record judged by what came before it. */
+pub fn find_anomalies(
+    period: &Period,
+    records: &[ExpenseRecord],
+    history: &[ExpenseRecord],
+    categories: &[ExpenseCategory],
+) -> Vec<Anomaly> {
+    let mut known: Vec<ExpenseRecord> = history.to_vec();
+    let mut sorted = records.to_vec();
+    sorted.sort_by_key(|r| r.logged_on());
+
+    let mut anomalies = Vec::new();
+    for record in sorted {
+        anomalies.extend(check_record(&record, &known, categories));
+        known.push(record);
+    }
+
+    anomalies.extend(check_monthly_jumps(period, records, history, categories));
+    anomalies
+}
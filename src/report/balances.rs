@@ -0,0 +1,75 @@
+use crate::database::expense::{ExpenseRecord, Settlement};
+
+/* Balances below this are treated as settled. */
+const EPSILON: f32 = 0.005;
+
+/*
+ * What every person is owed by the others, negative when they owe. Shared expenses credit
+ * the payer and debit everyone's share, settlements move the balance back.
+ */
+pub fn balances(records: &[ExpenseRecord], settlements: &[Settlement]) -> Vec<(String, f32)> {
+    let mut balances: Vec<(String, f32)> = Vec::new();
+    let mut book = |person: &str, amount: f32| match balances
+        .iter_mut()
+        .find(|(name, _)| name.eq_ignore_ascii_case(person))
+    {
+        Some((_, balance)) => *balance += amount,
+        None => balances.push((person.to_string(), amount)),
+    };
+
+    for record in records {
+        let Some(shared) = record.shared() else {
+            continue;
+        };
+
+        book(&shared.payer, record.amount());
+        for (person, share) in shared.shares(record.amount()) {
+            book(&person, -share);
+        }
+    }
+
+    for settlement in settlements {
+        book(&settlement.from, settlement.amount);
+        book(&settlement.to, -settlement.amount);
+    }
+
+    balances.sort_by(|a, b| b.1.total_cmp(&a.1));
+    balances
+}
+
+/*
+ * Transfers that bring every balance to zero. The biggest debtor pays the biggest creditor
+ * first, which settles one of them with every transfer.
+ */
+pub fn settle(balances: &[(String, f32)]) -> Vec<(String, String, f32)> {
+    let mut creditors: Vec<(String, f32)> = balances
+        .iter()
+        .filter(|(_, balance)| *balance > EPSILON)
+        .cloned()
+        .collect();
+    let mut debtors: Vec<(String, f32)> = balances
+        .iter()
+        .filter(|(_, balance)| *balance < -EPSILON)
+        .map(|(name, balance)| (name.clone(), -balance))
+        .collect();
+
+    let mut transfers = Vec::new();
+    loop {
+        creditors.sort_by(|a, b| b.1.total_cmp(&a.1));
+        debtors.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        let (Some(creditor), Some(debtor)) = (creditors.first_mut(), debtors.first_mut()) else {
+            break;
+        };
+
+        let amount = creditor.1.min(debtor.1);
+        transfers.push((debtor.0.clone(), creditor.0.clone(), amount));
+        creditor.1 -= amount;
+        debtor.1 -= amount;
+
+        creditors.retain(|(_, balance)| *balance > EPSILON);
+        debtors.retain(|(_, balance)| *balance > EPSILON);
+    }
+
+    transfers
+}
@@ -0,0 +1,212 @@
+use crate::{database::periods::Step, report::summary::ExpenseSummary};
+use chrono::{Datelike, NaiveDate};
+use std::{
+    env,
+    io::{IsTerminal, stdout},
+    process::{Command, Stdio},
+};
+
+const BAR_EIGHTHS: [char; 8] = ['▏', '▎', '▍', '▌', '▋', '▊', '▉', '█'];
+const SPARK_UNICODE: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+const SPARK_ASCII: [char; 8] = ['_', '.', '-', '~', '=', '+', '*', '#'];
+const HEAT_UNICODE: [char; 5] = ['·', '░', '▒', '▓', '█'];
+const HEAT_ASCII: [char; 5] = ['.', '-', '+', '*', '#'];
+const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+/* Output capabilities the charts adapt to. */
+#[derive(Debug, Clone, Copy)]
+pub struct ChartStyle {
+    pub unicode: bool,
+    pub width: usize,
+}
+
+impl ChartStyle {
+    /* Unicode blocks on a TTY, plain ASCII when the output is piped or redirected. */
+    pub fn detect() -> Self {
+        let unicode = stdout().is_terminal();
+        let width = terminal_width().unwrap_or(80).max(40);
+
+        Self { unicode, width }
+    }
+}
+
+fn terminal_width() -> Option<usize> {
+    if let Some(columns) = env::var("COLUMNS").ok().and_then(|c| c.parse().ok()) {
+        return Some(columns);
+    }
+
+    /* 'stty' reads the size of the terminal attached to its stdin. */
+    let output = Command::new("stty")
+        .arg("size")
+        .stdin(Stdio::inherit())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+
+    String::from_utf8(output.stdout)
+        .ok()?
+        .split_whitespace()
+        .nth(1)?
+        .parse()
+        .ok()
+}
+
+/* Horizontal bar per category, scaled to the biggest one. */
+pub fn bar_chart(summary: &ExpenseSummary, style: ChartStyle) -> String {
+    let label_width = summary
+        .categories
+        .iter()
+        .map(|c| c.name.chars().count())
+        .max()
+        .unwrap_or(0)
+        .min(24);
+    /* Label, two spaces, bar, space and a "123456.78" value. */
+    let bar_width = style.width.saturating_sub(label_width + 13).max(10);
+    let max = summary.categories.first().map_or(0.0, |c| c.total);
+
+    let mut chart = String::new();
+    for category in &summary.categories {
+        let ratio = if max > 0.0 { category.total / max } else { 0.0 };
+        let name: String = category.name.chars().take(label_width).collect();
+
+        chart.push_str(&format!(
+            "{:<label_width$}  {:<bar_width$} {:>9.2}\n",
+            name,
+            bar(ratio, bar_width, style.unicode),
+            category.total
+        ));
+    }
+
+    chart
+}
+
+fn bar(ratio: f32, width: usize, unicode: bool) -> String {
+    if !unicode {
+        return "#".repeat((ratio * width as f32).round() as usize);
+    }
+
+    let eighths = (ratio * width as f32 * 8.0).round() as usize;
+    let mut bar = "█".repeat(eighths / 8);
+    if !eighths.is_multiple_of(8) {
+        bar.push(BAR_EIGHTHS[eighths % 8 - 1]);
+    }
+    bar
+}
+
+/* Daily spending as a single line, days are bucketed when the period is wider than the terminal. */
+pub fn sparkline(summary: &ExpenseSummary, style: ChartStyle) -> String {
+    let levels = if style.unicode {
+        &SPARK_UNICODE
+    } else {
+        &SPARK_ASCII
+    };
+
+    let values: Vec<f32> = summary.daily.iter().map(|(_, amount)| *amount).collect();
+    let bucket = values.len().div_ceil(style.width).max(1);
+    let buckets: Vec<f32> = values.chunks(bucket).map(|c| c.iter().sum()).collect();
+    let max = buckets.iter().copied().fold(0.0, f32::max);
+
+    let line: String = buckets
+        .iter()
+        .map(|&value| {
+            if max <= 0.0 || value <= 0.0 {
+                if style.unicode { ' ' } else { '_' }
+            } else {
+                let level = ((value / max) * (levels.len() - 1) as f32).round() as usize;
+                levels[level]
+            }
+        })
+        .collect();
+
+    let mut chart = format!("{}\n", line);
+    if bucket > 1 {
+        chart.push_str(&format!("(each mark covers {} days)\n", bucket));
+    }
+    chart.push_str(&format!(
+        "{} .. {}   max {:.2} PLN\n",
+        summary.period.start(),
+        summary.period.end(),
+        max
+    ));
+
+    chart
+}
+
+/* GitHub-style grid: one row per weekday, one column per week. */
+pub fn heatmap(summary: &ExpenseSummary, style: ChartStyle) -> String {
+    let levels = if style.unicode {
+        &HEAT_UNICODE
+    } else {
+        &HEAT_ASCII
+    };
+
+    if summary.daily.is_empty() {
+        return String::new();
+    }
+
+    /* Weeks start on Monday, the grid starts at the week of the first day. */
+    let period_weeks = summary.period.split(Step::Week);
+    let week_of = |day: NaiveDate| {
+        period_weeks
+            .iter()
+            .position(|week| week.contains(day))
+            .unwrap_or(0)
+    };
+
+    let mut weeks = period_weeks.len().max(1);
+    /* Each week takes two columns, keep the latest weeks that fit next to the labels. */
+    let max_weeks = (style.width.saturating_sub(4) / 2).max(1);
+    let skipped = weeks.saturating_sub(max_weeks);
+    weeks -= skipped;
+
+    let max = summary
+        .daily
+        .iter()
+        .map(|(_, amount)| *amount)
+        .fold(0.0, f32::max);
+
+    let mut grid = vec![vec![' '; weeks]; 7];
+    let mut month_row = vec![' '; weeks * 2];
+    for &(day, amount) in &summary.daily {
+        let Some(week) = week_of(day).checked_sub(skipped) else {
+            continue;
+        };
+
+        let level = if amount <= 0.0 || max <= 0.0 {
+            0
+        } else {
+            1 + ((amount / max) * (levels.len() - 2) as f32).round() as usize
+        };
+        grid[day.weekday().num_days_from_monday() as usize][week] = levels[level];
+
+        if day.day() == 1 || (week == 0 && month_row[0] == ' ') {
+            /* A month starting right after the first column wins over the leading label. */
+            let mut pos = week * 2;
+            while pos > 0 && month_row[pos - 1] != ' ' {
+                pos -= 1;
+                month_row[pos] = ' ';
+            }
+
+            for (offset, ch) in day.format("%b").to_string().chars().enumerate() {
+                if let Some(slot) = month_row.get_mut(week * 2 + offset) {
+                    *slot = ch;
+                }
+            }
+        }
+    }
+
+    let mut chart = format!("    {}\n", month_row.iter().collect::<String>().trim_end());
+    for (weekday, row) in grid.iter().enumerate() {
+        let cells: String = row.iter().flat_map(|&cell| [cell, ' ']).collect();
+        chart.push_str(&format!("{} {}\n", WEEKDAYS[weekday], cells.trim_end()));
+    }
+
+    let legend: String = levels.iter().flat_map(|&l| [l, ' ']).collect();
+    chart.push_str(&format!(
+        "    less {} more   (max {:.2} PLN/day)\n",
+        legend.trim_end(),
+        max
+    ));
+
+    chart
+}
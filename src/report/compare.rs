@@ -0,0 +1,72 @@
+use crate::report::summary::ExpenseSummary;
+
+/* Category totals of two periods. None marks a category without expenses in that period. */
+#[derive(Debug, Clone)]
+pub struct CategoryDelta {
+    pub name: String,
+    pub base: Option<f32>,
+    pub current: Option<f32>,
+}
+
+impl CategoryDelta {
+    pub fn delta(&self) -> f32 {
+        self.current.unwrap_or(0.0) - self.base.unwrap_or(0.0)
+    }
+
+    /* Relative change, undefined when the category is new. */
+    pub fn percent(&self) -> Option<f32> {
+        match self.base {
+            Some(base) if base > 0.0 => Some(self.delta() / base * 100.0),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Comparison {
+    pub base: ExpenseSummary,
+    pub current: ExpenseSummary,
+    /* Sorted by the absolute change, the biggest first. */
+    pub categories: Vec<CategoryDelta>,
+}
+
+impl Comparison {
+    pub fn new(base: ExpenseSummary, current: ExpenseSummary) -> Self {
+        let mut categories: Vec<CategoryDelta> = base
+            .categories
+            .iter()
+            .map(|category| CategoryDelta {
+                name: category.name.clone(),
+                base: Some(category.total),
+                current: None,
+            })
+            .collect();
+
+        for category in &current.categories {
+            match categories.iter_mut().find(|c| c.name == category.name) {
+                Some(delta) => delta.current = Some(category.total),
+                None => categories.push(CategoryDelta {
+                    name: category.name.clone(),
+                    base: None,
+                    current: Some(category.total),
+                }),
+            }
+        }
+
+        categories.sort_by(|a, b| b.delta().abs().total_cmp(&a.delta().abs()));
+
+        Self {
+            base,
+            current,
+            categories,
+        }
+    }
+
+    pub fn total(&self) -> CategoryDelta {
+        CategoryDelta {
+            name: String::from("TOTAL"),
+            base: Some(self.base.grand_total),
+            current: Some(self.current.grand_total),
+        }
+    }
+}
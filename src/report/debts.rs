@@ -0,0 +1,85 @@
+use crate::database::expense::{Debt, ExpenseRecord};
+use chrono::NaiveDate;
+
+/* Balances below this are treated as paid off. */
+const EPSILON: f32 = 0.005;
+const DAYS_PER_YEAR: f32 = 365.0;
+
+/* State of a debt as of a day. */
+#[derive(Debug, Clone)]
+pub struct DebtBalance {
+    pub debt: Debt,
+    pub repaid: f32,
+    pub interest: f32,
+    /* Repayments ordered by date, as (date, amount). */
+    pub repayments: Vec<(NaiveDate, f32)>,
+}
+
+impl DebtBalance {
+    pub fn outstanding(&self) -> f32 {
+        self.debt.principal + self.interest - self.repaid
+    }
+
+    pub fn is_settled(&self) -> bool {
+        self.outstanding() < EPSILON
+    }
+}
+
+/*
+ * Balance of every debt from the records linked to it. Money moving in the direction
+ * opposite to the one of taking the debt pays it back. Interest accrues daily on the
+ * principal not paid back yet.
+ */
+pub fn debt_balances(
+    debts: &[Debt],
+    records: &[ExpenseRecord],
+    today: NaiveDate,
+) -> Vec<DebtBalance> {
+    debts
+        .iter()
+        .map(|debt| {
+            let outflow = debt.kind.outflow();
+            let mut repayments: Vec<(NaiveDate, f32)> = records
+                .iter()
+                .filter(|record| record.debt() == Some(debt.id) && record.logged_on() <= today)
+                .map(|record| (record.logged_on(), -record.amount() * outflow))
+                .filter(|(_, amount)| *amount > 0.0)
+                .collect();
+            repayments.sort_by_key(|(date, _)| *date);
+
+            let repaid = repayments.iter().fold(0.0, |sum, (_, amount)| sum + amount);
+            let interest = debt
+                .interest
+                .map_or(0.0, |rate| accrued_interest(debt, rate, &repayments, today));
+
+            DebtBalance {
+                debt: debt.clone(),
+                repaid,
+                interest,
+                repayments,
+            }
+        })
+        .collect()
+}
+
+fn accrued_interest(
+    debt: &Debt,
+    rate: f32,
+    repayments: &[(NaiveDate, f32)],
+    today: NaiveDate,
+) -> f32 {
+    let daily = |principal: f32, from: NaiveDate, to: NaiveDate| {
+        principal.max(0.0) * rate / 100.0 * (to - from).num_days().max(0) as f32 / DAYS_PER_YEAR
+    };
+
+    let mut principal = debt.principal;
+    let mut since = debt.logged_on;
+    let mut interest = 0.0;
+    for &(date, amount) in repayments {
+        interest += daily(principal, since, date);
+        principal -= amount;
+        since = date;
+    }
+
+    interest + daily(principal, since, today)
+}
@@ -0,0 +1,115 @@
+use crate::{
+    database::{
+        config::envelope::Leftover,
+        expense::{ExpenseCategory, ExpenseSheet},
+    },
+    report::ExpenseSummary,
+};
+
+/* Money of a category in a sheet. */
+#[derive(Debug, Clone)]
+pub struct Envelope {
+    pub category: String,
+    /* Left from the sheets before, negative when overspent. */
+    pub carried: f32,
+    pub assigned: f32,
+    pub spent: f32,
+}
+
+impl Envelope {
+    pub fn available(&self) -> f32 {
+        self.carried + self.assigned - self.spent
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct EnvelopeBudget {
+    pub envelopes: Vec<Envelope>,
+    pub income: f32,
+    /* Income of this and earlier sheets not put into any envelope yet. */
+    pub to_assign: f32,
+}
+
+impl EnvelopeBudget {
+    pub fn envelope(&self, category: &str) -> Option<&Envelope> {
+        self.envelopes
+            .iter()
+            .find(|e| e.category.eq_ignore_ascii_case(category))
+    }
+}
+
+/*
+ * Envelopes of the last sheet of the chain. Every sheet before passes on what was left in
+ * its envelopes, either to the same envelope or back to the money to be assigned.
+ */
+pub fn envelopes(
+    chain: &[ExpenseSheet],
+    categories: &[ExpenseCategory],
+    leftover: Leftover,
+) -> EnvelopeBudget {
+    let mut budget = EnvelopeBudget {
+        envelopes: categories
+            .iter()
+            .map(|category| Envelope {
+                category: category.name.clone(),
+                carried: 0.0,
+                assigned: 0.0,
+                spent: 0.0,
+            })
+            .collect(),
+        income: 0.0,
+        to_assign: 0.0,
+    };
+
+    for (idx, sheet) in chain.iter().enumerate() {
+        if idx > 0 {
+            for envelope in &mut budget.envelopes {
+                let left = envelope.available();
+                match leftover {
+                    Leftover::Rollover => envelope.carried = left,
+                    Leftover::Sweep => {
+                        envelope.carried = 0.0;
+                        budget.to_assign += left;
+                    }
+                }
+                envelope.assigned = 0.0;
+                envelope.spent = 0.0;
+            }
+        }
+
+        budget.income = sheet.income().iter().fold(0.0, |sum, r| sum + r.amount());
+        budget.to_assign += budget.income;
+
+        for assignment in sheet.assignments() {
+            envelope_mut(&mut budget.envelopes, &assignment.category).assigned += assignment.amount;
+            budget.to_assign -= assignment.amount;
+        }
+
+        let summary = ExpenseSummary::new(sheet.period.clone(), sheet.expenses(), categories);
+        for category in &summary.categories {
+            envelope_mut(&mut budget.envelopes, &category.name).spent += category.total;
+        }
+    }
+
+    budget
+}
+
+/* Categories no longer configured still keep their envelope. */
+fn envelope_mut<'a>(envelopes: &'a mut Vec<Envelope>, category: &str) -> &'a mut Envelope {
+    match envelopes
+        .iter()
+        .position(|e| e.category.eq_ignore_ascii_case(category))
+    {
+        Some(pos) => &mut envelopes[pos],
+        None => {
+            envelopes.push(Envelope {
+                category: category.to_string(),
+                carried: 0.0,
+                assigned: 0.0,
+                spent: 0.0,
+            });
+            let last = envelopes.len() - 1;
+            &mut envelopes[last]
+        }
+    }
+}
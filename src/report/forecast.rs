@@ -0,0 +1,301 @@
+use crate::{
+    database::{
+        expense::{ExpenseCategory, ExpenseRecord},
+        periods::Period,
+    },
+    report::{
+        patterns::{self, RepeatingCost},
+        summary::ExpenseSummary,
+    },
+};
+use chrono::{Datelike, Duration, NaiveDate};
+use std::collections::HashMap;
+
+/* Days of history the variable spending pattern is learned from. */
+const HISTORY_DAYS: i64 = 180;
+/* z-score of the two-sided 80% confidence band. */
+const BAND_Z: f32 = 1.28;
+
+#[derive(Debug, Clone)]
+pub struct CategoryForecast {
+    pub name: String,
+    pub spent: f32,
+    /* Repeating costs still expected in the period. */
+    pub fixed: f32,
+    /* Expected variable spending in the remaining days. */
+    pub variable: f32,
+    /* Half-width of the confidence band around the projected total. */
+    pub band: f32,
+    pub budget: Option<f32>,
+    /* First day the projected spending exceeds the budget. */
+    pub runs_out: Option<NaiveDate>,
+}
+
+impl CategoryForecast {
+    pub fn projected(&self) -> f32 {
+        self.spent + self.fixed + self.variable
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct UpcomingCharge {
+    pub date: NaiveDate,
+    pub label: String,
+    pub category: String,
+    pub amount: f32,
+}
+
+#[derive(Debug, Clone)]
+pub struct Forecast {
+    pub period: Period,
+    pub today: NaiveDate,
+    pub categories: Vec<CategoryForecast>,
+    pub upcoming: Vec<UpcomingCharge>,
+    pub spent: f32,
+    pub projected: f32,
+    pub low: f32,
+    pub high: f32,
+}
+
+/* Per category daily spending learned from history. */
+struct DailyPattern {
+    per_weekday: [f32; 7],
+    std_dev: f32,
+}
+
+impl Forecast {
+    /*
+     * 'history' holds the records from before the period. Repeating costs are projected on
+     * their expected dates, the rest by the average spending of each weekday.
+     */
+    pub fn new(
+        period: Period,
+        current: &[ExpenseRecord],
+        history: &[ExpenseRecord],
+        categories: &[ExpenseCategory],
+        today: NaiveDate,
+    ) -> Self {
+        let summary = ExpenseSummary::new(period.clone(), current, categories);
+
+        /* Split expenses are projected line by line, each in its own category. */
+        let spending = |records: &[ExpenseRecord]| -> Vec<ExpenseRecord> {
+            ExpenseRecord::itemize(records)
+                .into_iter()
+                .filter(|r| !r.is_debt())
+                .collect()
+        };
+        let current = &spending(current);
+        let history = &spending(history);
+
+        let all_records: Vec<ExpenseRecord> = history.iter().chain(current).cloned().collect();
+        let repeating = patterns::find_repeating(&all_records);
+
+        /* Days already accounted for by recorded expenses. */
+        let last_known = today.min(*period.end());
+        let first_open = (last_known + Duration::days(1)).max(*period.start());
+
+        let upcoming: Vec<UpcomingCharge> = repeating
+            .iter()
+            .flat_map(|cost| {
+                cost.charges_between(first_open - Duration::days(1), *period.end())
+                    .into_iter()
+                    .map(|date| upcoming_charge(cost, date))
+            })
+            .collect();
+
+        let variable_history: Vec<&ExpenseRecord> = history
+            .iter()
+            .filter(|r| !repeating.iter().any(|cost| cost.matches(r)))
+            .collect();
+        let patterns = learn_patterns(&variable_history, *period.start());
+
+        /* Without any history the pace of the period so far is the only hint. */
+        let elapsed = (last_known - *period.start()).num_days() + 1;
+        let fallback_rate = |category: &str| -> f32 {
+            if elapsed <= 0 {
+                return 0.0;
+            }
+            let spent: f32 = current
+                .iter()
+                .filter(|r| r.category() == category && !repeating.iter().any(|c| c.matches(r)))
+                .map(|r| r.amount())
+                .sum();
+            spent / elapsed as f32
+        };
+
+        let open_days: Vec<NaiveDate> = first_open
+            .iter_days()
+            .take_while(|day| day <= period.end())
+            .collect();
+
+        let mut names: Vec<String> = summary.categories.iter().map(|c| c.name.clone()).collect();
+        for name in patterns
+            .keys()
+            .chain(upcoming.iter().map(|u| &u.category))
+            .chain(summary.budgets.iter().map(|b| &b.name))
+        {
+            if !names.contains(name) {
+                names.push(name.clone());
+            }
+        }
+
+        let mut forecasts: Vec<CategoryForecast> = names
+            .into_iter()
+            .map(|name| {
+                let spent = summary
+                    .categories
+                    .iter()
+                    .find(|c| c.name == name)
+                    .map_or(0.0, |c| c.total);
+
+                let daily_rate = |day: NaiveDate| match patterns.get(&name) {
+                    Some(pattern) => {
+                        pattern.per_weekday[day.weekday().num_days_from_monday() as usize]
+                    }
+                    None => fallback_rate(&name),
+                };
+                let charges: Vec<&UpcomingCharge> =
+                    upcoming.iter().filter(|u| u.category == name).collect();
+
+                let variable = open_days
+                    .iter()
+                    .fold(0.0, |sum, &day| sum + daily_rate(day));
+                let fixed = charges.iter().fold(0.0, |sum, u| sum + u.amount);
+                let band = patterns.get(&name).map_or(0.0, |p| {
+                    BAND_Z * p.std_dev * (open_days.len() as f32).sqrt()
+                });
+
+                let budget = summary
+                    .budgets
+                    .iter()
+                    .find(|b| b.name == name)
+                    .map(|b| b.effective());
+
+                /* Walks the period, recorded days first, until spending crosses the budget. */
+                let runs_out = budget.and_then(|budget| {
+                    let mut total = 0.0;
+                    period
+                        .start()
+                        .iter_days()
+                        .take_while(|day| day <= period.end())
+                        .find(|&day| {
+                            total += if day <= last_known {
+                                current
+                                    .iter()
+                                    .filter(|r| r.logged_on() == day && r.category() == name)
+                                    .map(|r| r.amount())
+                                    .sum::<f32>()
+                            } else {
+                                daily_rate(day)
+                                    + charges
+                                        .iter()
+                                        .filter(|u| u.date == day)
+                                        .map(|u| u.amount)
+                                        .sum::<f32>()
+                            };
+                            total > budget
+                        })
+                });
+
+                CategoryForecast {
+                    name,
+                    spent,
+                    fixed,
+                    variable,
+                    band,
+                    budget,
+                    runs_out,
+                }
+            })
+            .collect();
+
+        forecasts.sort_by(|a, b| b.projected().total_cmp(&a.projected()));
+
+        let projected = forecasts.iter().fold(0.0, |sum, f| sum + f.projected());
+        /* Categories vary independently, so their bands add up in quadrature. */
+        let band = forecasts
+            .iter()
+            .map(|f| f.band * f.band)
+            .sum::<f32>()
+            .sqrt();
+        let spent = summary.grand_total;
+
+        Self {
+            period,
+            today,
+            categories: forecasts,
+            upcoming,
+            spent,
+            projected,
+            low: (projected - band).max(spent),
+            high: projected + band,
+        }
+    }
+}
+
+fn upcoming_charge(cost: &RepeatingCost, date: NaiveDate) -> UpcomingCharge {
+    UpcomingCharge {
+        date,
+        label: cost.label.clone(),
+        category: cost.category.clone(),
+        amount: cost.current_amount(),
+    }
+}
+
+/* Average spending per weekday and the spread of daily totals, for each category. */
+fn learn_patterns(history: &[&ExpenseRecord], before: NaiveDate) -> HashMap<String, DailyPattern> {
+    let window_start = before - Duration::days(HISTORY_DAYS);
+    let records: Vec<&&ExpenseRecord> = history
+        .iter()
+        .filter(|r| r.logged_on() >= window_start && r.logged_on() < before)
+        .collect();
+
+    /* The window starts with the first known record, not before it. */
+    let Some(first) = records.iter().map(|r| r.logged_on()).min() else {
+        return HashMap::new();
+    };
+    let days: Vec<NaiveDate> = first.iter_days().take_while(|d| *d < before).collect();
+
+    let mut weekday_count = [0usize; 7];
+    for day in &days {
+        weekday_count[day.weekday().num_days_from_monday() as usize] += 1;
+    }
+
+    let mut daily: HashMap<&str, HashMap<NaiveDate, f32>> = HashMap::new();
+    for record in &records {
+        *daily
+            .entry(record.category())
+            .or_default()
+            .entry(record.logged_on())
+            .or_default() += record.amount();
+    }
+
+    daily
+        .into_iter()
+        .map(|(category, totals)| {
+            let mut per_weekday = [0.0f32; 7];
+            for (day, amount) in &totals {
+                per_weekday[day.weekday().num_days_from_monday() as usize] += amount;
+            }
+            for (weekday, total) in per_weekday.iter_mut().enumerate() {
+                *total /= weekday_count[weekday].max(1) as f32;
+            }
+
+            let values: Vec<f32> = days
+                .iter()
+                .map(|day| totals.get(day).copied().unwrap_or(0.0))
+                .collect();
+            let mean = values.iter().sum::<f32>() / values.len() as f32;
+            let variance =
+                values.iter().map(|v| (v - mean).powi(2)).sum::<f32>() / values.len() as f32;
+
+            (
+                category.to_string(),
+                DailyPattern {
+                    per_weekday,
+                    std_dev: variance.sqrt(),
+                },
+            )
+        })
+        .collect()
+}
@@ -0,0 +1,302 @@
+use crate::{
+    database::expense::ExpenseRecord,
+    report::summary::{CategoryTotal, ExpenseSummary},
+};
+use std::{f32::consts::PI, fmt::Write};
+
+const PALETTE: [&str; 10] = [
+    "#4e79a7", "#f28e2b", "#e15759", "#76b7b2", "#59a14f", "#edc948", "#b07aa1", "#ff9da7",
+    "#9c755f", "#bab0ac",
+];
+
+const STYLE: &str = "\
+body{font-family:sans-serif;margin:2em auto;max-width:960px;color:#222}\
+h1{margin-bottom:0}.period{color:#666;margin-top:.2em}\
+section{margin:2em 0}.charts{display:flex;flex-wrap:wrap;gap:2em;align-items:center}\
+table{border-collapse:collapse;width:100%}th,td{padding:.3em .6em;border-bottom:1px solid #ddd}\
+th{text-align:left;background:#f4f4f4}td.num,th.num{text-align:right}\
+table.sortable th{cursor:pointer}table.sortable th:after{content:' \\2195';color:#aaa}\
+.over{color:#c0392b;font-weight:bold}.legend span{display:inline-block;width:.8em;height:.8em;margin-right:.4em}";
+
+/*
+ * Sorts a table by the clicked column, using 'data-v' when present. Cells compare as numbers
+ * only when the whole text is one, dates and other text compare as strings.
+ */
+const SORT_SCRIPT: &str = "\
+document.querySelectorAll('table.sortable th').forEach(function(th,col){\
+th.addEventListener('click',function(){\
+var body=th.closest('table').tBodies[0];var asc=th.dataset.dir!=='asc';th.dataset.dir=asc?'asc':'desc';\
+var rows=Array.from(body.rows);rows.sort(function(a,b){\
+var x=a.cells[col].dataset.v||a.cells[col].textContent;var y=b.cells[col].dataset.v||b.cells[col].textContent;\
+var nx=x.trim()===''?NaN:Number(x),ny=y.trim()===''?NaN:Number(y);var r=(!isNaN(nx)&&!isNaN(ny))?nx-ny:x.localeCompare(y);\
+return asc?r:-r;});rows.forEach(function(r){body.appendChild(r);});});});";
+
+pub fn render(title: &str, summary: &ExpenseSummary, records: &[ExpenseRecord]) -> String {
+    let mut html = String::new();
+
+    let _ = write!(
+        html,
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>{title}</title>\n<style>{STYLE}</style>\n</head>\n<body>\n\
+         <h1>{title}</h1>\n<p class=\"period\">{} &ndash; {} &middot; {} expenses &middot; {:.2} PLN</p>\n",
+        summary.period.start(),
+        summary.period.end(),
+        summary.count(),
+        summary.grand_total,
+        title = escape(title),
+    );
+    if summary.refunded > 0.0 {
+        let _ = writeln!(
+            html,
+            "<p class=\"period\">{:.2} PLN gross, {:.2} PLN refunded</p>",
+            summary.grand_total + summary.refunded,
+            summary.refunded
+        );
+    }
+
+    html.push_str("<section>\n<h2>Categories</h2>\n<div class=\"charts\">\n");
+    html.push_str(&pie_chart(summary));
+    html.push_str(&bar_chart(summary));
+    html.push_str("</div>\n</section>\n");
+
+    html.push_str("<section>\n<h2>Daily spending</h2>\n");
+    html.push_str(&daily_line(summary));
+    html.push_str("</section>\n");
+
+    if !summary.budgets.is_empty() {
+        html.push_str("<section>\n<h2>Budget vs. actual</h2>\n");
+        html.push_str(&budget_table(summary));
+        html.push_str("</section>\n");
+    }
+
+    html.push_str("<section>\n<h2>Records</h2>\n");
+    html.push_str(&record_table(records));
+    html.push_str("</section>\n");
+
+    let _ = write!(html, "<script>{SORT_SCRIPT}</script>\n</body>\n</html>\n");
+
+    html
+}
+
+fn pie_chart(summary: &ExpenseSummary) -> String {
+    const R: f32 = 90.0;
+    const C: f32 = 100.0;
+
+    let mut svg = String::from(
+        "<svg width=\"200\" height=\"200\" viewBox=\"0 0 200 200\" role=\"img\" aria-label=\"Category share\">\n",
+    );
+
+    /* Refunds can leave a category below zero, it takes no share of the pie then. */
+    let shares: Vec<&CategoryTotal> = summary
+        .categories
+        .iter()
+        .filter(|category| category.total > 0.0)
+        .collect();
+    let total = shares
+        .iter()
+        .fold(0.0, |sum, category| sum + category.total);
+
+    if total <= 0.0 {
+        let _ = writeln!(
+            svg,
+            "<circle cx=\"{C}\" cy=\"{C}\" r=\"{R}\" fill=\"#eee\"/>"
+        );
+    } else if shares.len() == 1 {
+        let _ = writeln!(
+            svg,
+            "<circle cx=\"{C}\" cy=\"{C}\" r=\"{R}\" fill=\"{}\"><title>{}</title></circle>",
+            PALETTE[0],
+            escape(&shares[0].name)
+        );
+    } else {
+        let mut angle = -PI / 2.0;
+        for (idx, category) in shares.iter().enumerate() {
+            let sweep = category.total / total * 2.0 * PI;
+            let (x1, y1) = (C + R * angle.cos(), C + R * angle.sin());
+            angle += sweep;
+            let (x2, y2) = (C + R * angle.cos(), C + R * angle.sin());
+            let large_arc = if sweep > PI { 1 } else { 0 };
+
+            let _ = writeln!(
+                svg,
+                "<path d=\"M{C},{C} L{x1:.2},{y1:.2} A{R},{R} 0 {large_arc} 1 {x2:.2},{y2:.2} Z\" \
+                 fill=\"{}\"><title>{} {:.1}%</title></path>",
+                PALETTE[idx % PALETTE.len()],
+                escape(&category.name),
+                category.total / total * 100.0
+            );
+        }
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn bar_chart(summary: &ExpenseSummary) -> String {
+    const ROW: f32 = 26.0;
+    const LABEL: f32 = 140.0;
+    const WIDTH: f32 = 560.0;
+
+    let max = summary
+        .categories
+        .first()
+        .map_or(0.0, |c| c.total)
+        .max(f32::EPSILON);
+    let height = ROW * summary.categories.len() as f32;
+
+    let mut svg = format!(
+        "<svg width=\"{WIDTH}\" height=\"{height}\" viewBox=\"0 0 {WIDTH} {height}\" role=\"img\" \
+         aria-label=\"Category totals\" font-size=\"12\">\n"
+    );
+
+    for (idx, category) in summary.categories.iter().enumerate() {
+        let y = idx as f32 * ROW;
+        let bar = ((WIDTH - LABEL - 90.0) * category.total / max).max(0.0);
+        let _ = writeln!(
+            svg,
+            "<text x=\"0\" y=\"{:.1}\">{}</text>\
+             <rect x=\"{LABEL}\" y=\"{:.1}\" width=\"{bar:.1}\" height=\"{:.1}\" fill=\"{}\"/>\
+             <text x=\"{:.1}\" y=\"{:.1}\">{:.2}</text>",
+            y + 17.0,
+            escape(&category.name),
+            y + 4.0,
+            ROW - 8.0,
+            PALETTE[idx % PALETTE.len()],
+            LABEL + bar + 6.0,
+            y + 17.0,
+            category.total
+        );
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn daily_line(summary: &ExpenseSummary) -> String {
+    const WIDTH: f32 = 900.0;
+    const HEIGHT: f32 = 220.0;
+    const PAD: f32 = 40.0;
+
+    let max = summary
+        .daily
+        .iter()
+        .map(|(_, amount)| *amount)
+        .fold(0.0, f32::max)
+        .max(f32::EPSILON);
+    let step = (WIDTH - 2.0 * PAD) / (summary.daily.len().max(2) - 1) as f32;
+
+    let points: Vec<String> = summary
+        .daily
+        .iter()
+        .enumerate()
+        .map(|(idx, (_, amount))| {
+            let x = PAD + idx as f32 * step;
+            let y = HEIGHT - PAD - (HEIGHT - 2.0 * PAD) * amount / max;
+            format!("{x:.1},{y:.1}")
+        })
+        .collect();
+
+    let mut svg = format!(
+        "<svg width=\"{WIDTH}\" height=\"{HEIGHT}\" viewBox=\"0 0 {WIDTH} {HEIGHT}\" role=\"img\" \
+         aria-label=\"Daily spending\" font-size=\"11\">\n\
+         <line x1=\"{PAD}\" y1=\"{b}\" x2=\"{r}\" y2=\"{b}\" stroke=\"#999\"/>\n\
+         <line x1=\"{PAD}\" y1=\"{PAD}\" x2=\"{PAD}\" y2=\"{b}\" stroke=\"#999\"/>\n\
+         <text x=\"2\" y=\"{PAD}\">{max:.0}</text><text x=\"2\" y=\"{b}\">0</text>\n\
+         <polyline fill=\"none\" stroke=\"{}\" stroke-width=\"2\" points=\"{}\"/>\n",
+        PALETTE[0],
+        points.join(" "),
+        b = HEIGHT - PAD,
+        r = WIDTH - PAD,
+    );
+
+    if let (Some((first, _)), Some((last, _))) = (summary.daily.first(), summary.daily.last()) {
+        let _ = writeln!(
+            svg,
+            "<text x=\"{PAD}\" y=\"{}\">{first}</text>\
+             <text x=\"{}\" y=\"{}\" text-anchor=\"end\">{last}</text>",
+            HEIGHT - PAD + 16.0,
+            WIDTH - PAD,
+            HEIGHT - PAD + 16.0
+        );
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn budget_table(summary: &ExpenseSummary) -> String {
+    let mut table = String::from(
+        "<table>\n<thead><tr><th>Category</th><th class=\"num\">Budget</th>\
+         <th class=\"num\">Carried</th><th class=\"num\">Effective</th><th class=\"num\">Actual</th><th class=\"num\">Remaining</th><th class=\"num\">Used</th></tr></thead>\n<tbody>\n",
+    );
+
+    for line in &summary.budgets {
+        let used = if line.effective() > 0.0 {
+            line.spent / line.effective() * 100.0
+        } else {
+            0.0
+        };
+        let class = if line.remaining() < 0.0 {
+            " class=\"num over\""
+        } else {
+            " class=\"num\""
+        };
+
+        let _ = writeln!(
+            table,
+            "<tr><td>{}</td><td class=\"num\">{:.2}</td><td class=\"num\">{:+.2}</td>\
+             <td class=\"num\">{:.2}</td><td class=\"num\">{:.2}</td>\
+             <td{class}>{:.2}</td><td{class}>{used:.1}%</td></tr>",
+            escape(&line.name),
+            line.budget,
+            line.carried,
+            line.effective(),
+            line.spent,
+            line.remaining()
+        );
+    }
+
+    table.push_str("</tbody>\n</table>\n");
+    table
+}
+
+fn record_table(records: &[ExpenseRecord]) -> String {
+    let mut table = String::from(
+        "<table class=\"sortable\">\n<thead><tr><th>Date</th><th>Category</th>\
+         <th class=\"num\">Amount</th><th>Payee</th><th>Note</th><th>Tags</th></tr></thead>\n<tbody>\n",
+    );
+
+    /* Split expenses take a row per line, as they are summed up per category. */
+    for record in ExpenseRecord::itemize(records) {
+        let _ = writeln!(
+            table,
+            "<tr><td>{}</td><td>{}</td><td class=\"num\" data-v=\"{}\">{:.2}</td>\
+             <td>{}</td><td>{}</td><td>{}</td></tr>",
+            record.logged_on(),
+            escape(record.category()),
+            record.amount(),
+            record.amount(),
+            escape(record.payee().unwrap_or_default()),
+            escape(record.note().unwrap_or_default()),
+            escape(&record.tags().join(", "))
+        );
+    }
+
+    table.push_str("</tbody>\n</table>\n");
+    table
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
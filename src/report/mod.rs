@@ -0,0 +1,15 @@
+pub mod accounts;
+pub mod anomaly;
+pub mod balances;
+pub mod chart;
+pub mod compare;
+pub mod debts;
+pub mod envelopes;
+pub mod forecast;
+pub mod html;
+pub mod patterns;
+pub mod stats;
+pub mod summary;
+
+/* Re-exports */
+pub use summary::ExpenseSummary;
@@ -0,0 +1,155 @@
+use crate::{database::expense::ExpenseRecord, report::stats::median};
+use chrono::{Duration, NaiveDate};
+use std::collections::HashMap;
+
+/* Occurrences needed before a cost is considered repeating. */
+const MIN_OCCURRENCES: usize = 3;
+/* Allowed relative deviation of an amount from the typical one. */
+const AMOUNT_TOLERANCE: f32 = 0.25;
+/* Allowed relative deviation of an interval from the typical one. */
+const INTERVAL_TOLERANCE: f32 = 0.2;
+/* Average lengths of a month and a year in days, used to scale the cost of a charge. */
+const DAYS_PER_MONTH: f32 = 30.44;
+const DAYS_PER_YEAR: f32 = 365.25;
+
+/* A cost charged at a regular interval with a similar amount, e.g. rent or a subscription. */
+#[derive(Debug, Clone)]
+pub struct RepeatingCost {
+    /* Payee or note the charges share, or the category with the amount when neither is set. */
+    pub label: String,
+    pub category: String,
+    pub typical_amount: f32,
+    pub interval_days: i64,
+    /* Charges ordered by date. */
+    pub charges: Vec<(NaiveDate, f32)>,
+    key: String,
+}
+
+impl RepeatingCost {
+    pub fn last_charge(&self) -> Option<&(NaiveDate, f32)> {
+        self.charges.last()
+    }
+
+    /* The amount of the latest charge, prices change over time. */
+    pub fn current_amount(&self) -> f32 {
+        self.last_charge().map_or(self.typical_amount, |c| c.1)
+    }
+
+    pub fn next_charge(&self) -> Option<NaiveDate> {
+        self.last_charge()
+            .map(|&(date, _)| date + Duration::days(self.interval_days))
+    }
+
+    /* The expected charge was missed by more than the interval tolerance. */
+    pub fn is_lapsed(&self, today: NaiveDate) -> bool {
+        let grace = (self.interval_days as f32 * INTERVAL_TOLERANCE).ceil() as i64;
+        self.next_charge()
+            .is_some_and(|next| next + Duration::days(grace) < today)
+    }
+
+    pub fn monthly_cost(&self) -> f32 {
+        self.current_amount() * DAYS_PER_MONTH / self.interval_days as f32
+    }
+
+    pub fn annual_cost(&self) -> f32 {
+        self.current_amount() * DAYS_PER_YEAR / self.interval_days as f32
+    }
+
+    /* Charges whose amount differs from the one before, as (date, old amount, new amount). */
+    pub fn price_changes(&self) -> Vec<(NaiveDate, f32, f32)> {
+        self.charges
+            .windows(2)
+            .filter(|pair| (pair[1].1 - pair[0].1).abs() >= 0.01)
+            .map(|pair| (pair[1].0, pair[0].1, pair[1].1))
+            .collect()
+    }
+
+    /* Expected charge dates after 'after', up to and including 'until'. */
+    pub fn charges_between(&self, after: NaiveDate, until: NaiveDate) -> Vec<NaiveDate> {
+        let Some(&(mut date, _)) = self.last_charge() else {
+            return Vec::new();
+        };
+
+        let mut dates = Vec::new();
+        while date <= until {
+            date += Duration::days(self.interval_days);
+            if date > after && date <= until {
+                dates.push(date);
+            }
+        }
+
+        dates
+    }
+
+    pub fn matches(&self, record: &ExpenseRecord) -> bool {
+        record_key(record) == self.key
+    }
+}
+
+fn record_key(record: &ExpenseRecord) -> String {
+    match record.payee().or(record.note()) {
+        Some(label) => format!("{}|{}", record.category(), label.trim().to_lowercase()),
+        None => format!("{}|{:.0}", record.category(), record.amount()),
+    }
+}
+
+/* Finds costs repeating at a regular interval with similar amounts. */
+pub fn find_repeating(records: &[ExpenseRecord]) -> Vec<RepeatingCost> {
+    let mut groups: HashMap<String, Vec<&ExpenseRecord>> = HashMap::new();
+    for record in records.iter().filter(|r| !r.is_refund() && !r.is_debt()) {
+        groups.entry(record_key(record)).or_default().push(record);
+    }
+
+    let mut repeating: Vec<RepeatingCost> = groups
+        .into_iter()
+        .filter_map(|(key, mut group)| {
+            if group.len() < MIN_OCCURRENCES {
+                return None;
+            }
+            group.sort_by_key(|r| r.logged_on());
+
+            let mut amounts: Vec<f32> = group.iter().map(|r| r.amount()).collect();
+            amounts.sort_by(f32::total_cmp);
+            let typical_amount = median(&amounts);
+
+            /* Prices change over time, so only consecutive charges need to be similar. */
+            let similar = group.windows(2).all(|pair| {
+                let (a, b) = (pair[0].amount(), pair[1].amount());
+                (a - b).abs() <= a.max(b) * AMOUNT_TOLERANCE
+            });
+
+            let intervals: Vec<i64> = group
+                .windows(2)
+                .map(|pair| (pair[1].logged_on() - pair[0].logged_on()).num_days())
+                .collect();
+            let mut sorted_intervals = intervals.clone();
+            sorted_intervals.sort_unstable();
+            let interval_days = sorted_intervals[sorted_intervals.len() / 2];
+
+            let regular = interval_days >= 5
+                && intervals.iter().all(|&interval| {
+                    (interval - interval_days).abs() as f32
+                        <= interval_days as f32 * INTERVAL_TOLERANCE
+                });
+
+            (similar && regular).then(|| {
+                let first = group[0];
+                RepeatingCost {
+                    label: first
+                        .payee()
+                        .or(first.note())
+                        .map(str::to_string)
+                        .unwrap_or_else(|| format!("{} {:.2}", first.category(), first.amount())),
+                    category: first.category().to_string(),
+                    typical_amount,
+                    interval_days,
+                    charges: group.iter().map(|r| (r.logged_on(), r.amount())).collect(),
+                    key,
+                }
+            })
+        })
+        .collect();
+
+    repeating.sort_by(|a, b| a.label.cmp(&b.label));
+    repeating
+}
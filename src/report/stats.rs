@@ -0,0 +1,140 @@
+use crate::database::{expense::ExpenseRecord, periods::Period};
+use chrono::{Datelike, NaiveDate};
+use serde::Serialize;
+use std::collections::HashMap;
+
+const TOP_COUNT: usize = 5;
+const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PayeeTotal {
+    pub payee: String,
+    pub total: f32,
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WeekdayTotal {
+    pub weekday: &'static str,
+    pub total: f32,
+    pub count: usize,
+}
+
+/* Spending pace: where the period ends if spending continues like so far. */
+#[derive(Debug, Clone, Serialize)]
+pub struct RunRate {
+    pub days_elapsed: i64,
+    pub days_remaining: i64,
+    pub projected_total: f32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExpenseStats {
+    pub total: f32,
+    pub count: usize,
+    pub daily_average: f32,
+    pub median_expense: f32,
+    pub max_expense: Option<ExpenseRecord>,
+    pub per_weekday: Vec<WeekdayTotal>,
+    pub top_payees: Vec<PayeeTotal>,
+    pub biggest_expenses: Vec<ExpenseRecord>,
+    /* Only for a period which is in progress. */
+    pub run_rate: Option<RunRate>,
+}
+
+impl ExpenseStats {
+    pub fn new(period: &Period, records: &[ExpenseRecord], today: NaiveDate) -> Self {
+        let records: &[ExpenseRecord] = &records
+            .iter()
+            .filter(|r| !r.is_debt())
+            .cloned()
+            .collect::<Vec<_>>();
+        let total = records.iter().fold(0.0, |sum, r| sum + r.amount());
+        /* Refunds lower the totals, but are not expenses of their own. */
+        let expenses: Vec<ExpenseRecord> =
+            records.iter().filter(|r| !r.is_refund()).cloned().collect();
+
+        let mut amounts: Vec<f32> = expenses.iter().map(|r| r.amount()).collect();
+        amounts.sort_by(f32::total_cmp);
+        let median_expense = median(&amounts);
+
+        let mut biggest_expenses = expenses.clone();
+        biggest_expenses.sort_by(|a, b| b.amount().total_cmp(&a.amount()));
+        biggest_expenses.truncate(TOP_COUNT);
+        let max_expense = biggest_expenses.first().cloned();
+
+        let mut per_weekday: Vec<WeekdayTotal> = WEEKDAYS
+            .iter()
+            .map(|&weekday| WeekdayTotal {
+                weekday,
+                total: 0.0,
+                count: 0,
+            })
+            .collect();
+        for record in records {
+            let day =
+                &mut per_weekday[record.logged_on().weekday().num_days_from_monday() as usize];
+            day.total += record.amount();
+            if !record.is_refund() {
+                day.count += 1;
+            }
+        }
+
+        let mut payees: HashMap<&str, PayeeTotal> = HashMap::new();
+        for record in records {
+            let Some(payee) = record.payee() else {
+                continue;
+            };
+            let entry = payees.entry(payee).or_insert_with(|| PayeeTotal {
+                payee: payee.to_string(),
+                total: 0.0,
+                count: 0,
+            });
+            entry.total += record.amount();
+            if !record.is_refund() {
+                entry.count += 1;
+            }
+        }
+        let mut top_payees: Vec<PayeeTotal> = payees.into_values().collect();
+        top_payees.sort_by(|a, b| b.total.total_cmp(&a.total));
+        top_payees.truncate(TOP_COUNT);
+
+        let days_total = period.days();
+        let days_elapsed = Period::new(NaiveDate::MIN, today)
+            .ok()
+            .and_then(|past| period.intersection(&past))
+            .map_or(0, |elapsed| elapsed.days());
+        let daily_average = if days_elapsed > 0 {
+            total / days_elapsed as f32
+        } else {
+            0.0
+        };
+
+        let run_rate = (days_elapsed > 0 && days_elapsed < days_total).then(|| RunRate {
+            days_elapsed,
+            days_remaining: days_total - days_elapsed,
+            projected_total: daily_average * days_total as f32,
+        });
+
+        Self {
+            total,
+            count: expenses.len(),
+            daily_average,
+            median_expense,
+            max_expense,
+            per_weekday,
+            top_payees,
+            biggest_expenses,
+            run_rate,
+        }
+    }
+}
+
+/* Median of sorted values. */
+pub fn median(sorted: &[f32]) -> f32 {
+    match sorted.len() {
+        0 => 0.0,
+        len if len % 2 == 0 => (sorted[len / 2 - 1] + sorted[len / 2]) / 2.0,
+        len => sorted[len / 2],
+    }
+}
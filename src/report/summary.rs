@@ -0,0 +1,235 @@
+use crate::database::{
+    expense::{BudgetRollover, ExpenseCategory, ExpenseRecord, ExpenseSheet, IncomeRecord},
+    periods::{Period, Step},
+};
+use chrono::{Datelike, NaiveDate};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone)]
+pub struct CategoryTotal {
+    pub name: String,
+    /* Net of refunds. */
+    pub total: f32,
+    /* Expenses, refunds not counted. */
+    pub count: usize,
+    pub refunded: f32,
+}
+
+impl CategoryTotal {
+    pub fn gross(&self) -> f32 {
+        self.total + self.refunded
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BudgetLine {
+    pub name: String,
+    pub budget: f32,
+    /* Left over from the sheets before, negative for carried overspending. */
+    pub carried: f32,
+    pub spent: f32,
+}
+
+impl BudgetLine {
+    /* The budget of the period with the carry-over. */
+    pub fn effective(&self) -> f32 {
+        self.budget + self.carried
+    }
+
+    pub fn remaining(&self) -> f32 {
+        self.effective() - self.spent
+    }
+}
+
+/* Aggregated view over a set of records. Shared by every report and chart. */
+#[derive(Debug, Clone)]
+pub struct ExpenseSummary {
+    pub period: Period,
+    /* Sorted by total, the biggest category first. */
+    pub categories: Vec<CategoryTotal>,
+    pub budgets: Vec<BudgetLine>,
+    /* One entry per day of the period, days without expenses included. */
+    pub daily: Vec<(NaiveDate, f32)>,
+    /* Net of refunds. */
+    pub grand_total: f32,
+    pub refunded: f32,
+    /* Expenses, a split expense counted once and refunds not counted. */
+    count: usize,
+}
+
+impl ExpenseSummary {
+    pub fn new(period: Period, records: &[ExpenseRecord], categories: &[ExpenseCategory]) -> Self {
+        let mut totals: HashMap<&str, CategoryTotal> = HashMap::new();
+        let mut per_day: HashMap<NaiveDate, f32> = HashMap::new();
+        let mut grand_total = 0.0;
+        let mut refunded = 0.0;
+        let mut count = 0;
+
+        for record in records.iter().filter(|r| !r.is_debt()) {
+            /* Every line of a split expense counts towards its own category. */
+            let mut counted: Vec<&str> = Vec::new();
+            for (category, amount) in record.parts() {
+                let category_total = totals.entry(category).or_insert_with(|| CategoryTotal {
+                    name: category.to_string(),
+                    total: 0.0,
+                    count: 0,
+                    refunded: 0.0,
+                });
+
+                category_total.total += amount;
+                if record.is_refund() {
+                    category_total.refunded -= amount;
+                    refunded -= amount;
+                } else if !counted.contains(&category) {
+                    /* The expense counts once in each category it touches. */
+                    category_total.count += 1;
+                    counted.push(category);
+                }
+            }
+            if !record.is_refund() {
+                count += 1;
+            }
+
+            *per_day.entry(record.logged_on()).or_default() += record.amount();
+            grand_total += record.amount();
+        }
+
+        let months = covered_months(&period);
+        let budgets = categories
+            .iter()
+            .filter_map(|category| {
+                let budget = category.budget?;
+                Some(BudgetLine {
+                    name: category.name.clone(),
+                    budget: budget * months,
+                    carried: 0.0,
+                    spent: totals.get(category.name.as_str()).map_or(0.0, |t| t.total),
+                })
+            })
+            .collect();
+
+        let mut categories: Vec<CategoryTotal> = totals.into_values().collect();
+        categories.sort_by(|a, b| b.total.total_cmp(&a.total));
+
+        let daily = period
+            .split(Step::Day)
+            .iter()
+            .map(|day| {
+                (
+                    *day.start(),
+                    per_day.get(day.start()).copied().unwrap_or(0.0),
+                )
+            })
+            .collect();
+
+        Self {
+            period,
+            categories,
+            budgets,
+            daily,
+            grand_total,
+            refunded,
+            count,
+        }
+    }
+
+    /* Budgets with the amounts carried over from the sheets before. */
+    pub fn with_carried(mut self, carried: &HashMap<String, f32>) -> Self {
+        for line in &mut self.budgets {
+            line.carried = carried.get(&line.name).copied().unwrap_or(0.0);
+        }
+        self
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn part_of_total(&self, category: &CategoryTotal) -> f32 {
+        if self.grand_total > 0.0 {
+            (category.total / self.grand_total) * 100.0
+        } else {
+            0.0
+        }
+    }
+}
+
+/*
+ * Budget carried into the sheet following the given ones, ordered oldest first. Every sheet
+ * passes on what was left of its budget with the carry-over, as set for the category.
+ */
+pub fn carried_budgets(
+    earlier: &[ExpenseSheet],
+    categories: &[ExpenseCategory],
+) -> HashMap<String, f32> {
+    let mut carried: HashMap<String, f32> = HashMap::new();
+
+    for sheet in earlier {
+        let summary = ExpenseSummary::new(sheet.period.clone(), sheet.expenses(), categories)
+            .with_carried(&carried);
+
+        for line in &summary.budgets {
+            let rollover = categories
+                .iter()
+                .find(|c| c.name == line.name)
+                .map_or(BudgetRollover::Off, |c| c.rollover);
+            let left = line.remaining();
+            let carry = match rollover {
+                BudgetRollover::Off => 0.0,
+                BudgetRollover::Unused => left.max(0.0),
+                BudgetRollover::All => left,
+            };
+            carried.insert(line.name.clone(), carry);
+        }
+    }
+
+    carried
+}
+
+/* Income of a period per source, compared with the expenses of the same period. */
+#[derive(Debug, Clone)]
+pub struct IncomeSummary {
+    /* Sorted by amount, the biggest source first. */
+    pub sources: Vec<(String, f32)>,
+    pub total: f32,
+}
+
+impl IncomeSummary {
+    pub fn new(income: &[IncomeRecord]) -> Self {
+        let mut sources: Vec<(String, f32)> = Vec::new();
+        for record in income {
+            match sources
+                .iter_mut()
+                .find(|(source, _)| source.eq_ignore_ascii_case(record.source()))
+            {
+                Some((_, total)) => *total += record.amount(),
+                None => sources.push((record.source().to_string(), record.amount())),
+            }
+        }
+        sources.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        let total = sources.iter().fold(0.0, |sum, (_, amount)| sum + amount);
+        Self { sources, total }
+    }
+
+    pub fn net(&self, expenses: f32) -> f32 {
+        self.total - expenses
+    }
+
+    /* Part of the income left after expenses, None without any income. */
+    pub fn savings_rate(&self, expenses: f32) -> Option<f32> {
+        (self.total > 0.0).then(|| self.net(expenses) / self.total * 100.0)
+    }
+}
+
+/* Number of calendar months covered by the period, partial months counted by days. */
+pub fn covered_months(period: &Period) -> f32 {
+    period
+        .split(Step::Month)
+        .iter()
+        .filter_map(|part| {
+            let month = Period::month(part.start().month(), part.start().year()).ok()?;
+            Some(part.days() as f32 / month.days() as f32)
+        })
+        .sum()
+}
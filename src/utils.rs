@@ -1,4 +1,3 @@
-use dirs;
 use std::{path::PathBuf, sync::OnceLock};
 
 static USER_CONFIG_DIR: OnceLock<PathBuf> = OnceLock::new();
@@ -20,6 +19,10 @@ pub fn sheets_dir() -> PathBuf {
     btr_dir().join("sheets")
 }
 
+pub fn reports_dir() -> PathBuf {
+    btr_dir().join("reports")
+}
+
 pub fn state_dir() -> PathBuf {
     let system_state_dir = APP_STATE_DIR.get_or_init(|| {
         dirs::data_local_dir()